@@ -0,0 +1,69 @@
+use advent2023::day10_pipe_maze::generator::MazeGenerator;
+use advent2023::day10_pipe_maze::map::Map;
+use advent2023::day10_pipe_maze::path::Path;
+use criterion::{criterion_group, criterion_main, Criterion};
+
+const SIZE: usize = 2000;
+
+// A single loop going through every sector of an even sized square: east along the top
+// row, snaking west and east over the other columns, and back north along the first column.
+fn serpentine_maze(size: usize) -> String {
+    (0..size)
+        .map(|y| {
+            (0..size)
+                .map(|x| match (y, x) {
+                    (0, 0) => 'S',
+                    (0, x) if x == size - 1 => '7',
+                    (y, 0) if y == size - 1 => 'L',
+                    (_, 0) => '|',
+                    (y, x) if x == size - 1 && y % 2 == 1 => 'J',
+                    (_, x) if x == size - 1 => '7',
+                    (y, 1) if y == size - 1 => '-',
+                    (y, 1) if y % 2 == 1 => 'F',
+                    (_, 1) if y > 0 => 'L',
+                    _ => '-',
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn bench_day10(c: &mut Criterion) {
+    let data = serpentine_maze(SIZE);
+    let mut group = c.benchmark_group("day10 2000x2000");
+    group.sample_size(10);
+    group.bench_function("parse map", |b| b.iter(|| data.parse::<Map>().unwrap()));
+    let map: Map = data.parse().unwrap();
+    group.bench_function("find loop", |b| b.iter(|| Path::new(&map).unwrap()));
+    let path = Path::new(&map).unwrap();
+    group.bench_function("ray casting", |b| {
+        b.iter(|| path.get_sectors_inside_paths().len())
+    });
+    group.bench_function("shoelace", |b| b.iter(|| path.count_sectors_inside_path()));
+    group.finish();
+}
+
+fn bench_day10_generated(c: &mut Criterion) {
+    let generator = MazeGenerator {
+        width: SIZE,
+        height: SIZE,
+        coverage: 0.8,
+        ..Default::default()
+    };
+    let mut group = c.benchmark_group("day10 generated 2000x2000");
+    group.sample_size(10);
+    group.bench_function("generate", |b| b.iter(|| generator.generate().unwrap()));
+    let maze = generator.generate().unwrap();
+    let map: Map = maze.data.parse().unwrap();
+    group.bench_function("find loop", |b| b.iter(|| Path::new(&map).unwrap()));
+    let path = Path::new(&map).unwrap();
+    group.bench_function("ray casting", |b| {
+        b.iter(|| path.get_sectors_inside_paths().len())
+    });
+    group.bench_function("shoelace", |b| b.iter(|| path.count_sectors_inside_path()));
+    group.finish();
+}
+
+criterion_group!(benches, bench_day10, bench_day10_generated);
+criterion_main!(benches);
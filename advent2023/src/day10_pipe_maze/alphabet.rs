@@ -0,0 +1,146 @@
+use super::pipe_type::{PipeType, PipeTypeError, UnknownTileError};
+use std::collections::HashMap;
+
+const CLASSIC_TILES: [(char, PipeType); 8] = [
+    ('|', PipeType::NorthSouth),
+    ('-', PipeType::EastWest),
+    ('L', PipeType::NorthEast),
+    ('J', PipeType::NorthWest),
+    ('7', PipeType::SouthWest),
+    ('F', PipeType::SouthEast),
+    ('.', PipeType::Ground),
+    ('S', PipeType::StartingPosition),
+];
+
+// The characters a map can be written with and the tile each one stands for.
+#[derive(Debug, Clone)]
+pub struct TileAlphabet {
+    tiles: HashMap<char, PipeType>,
+}
+
+// The alphabet of the puzzle input.
+impl Default for TileAlphabet {
+    fn default() -> Self {
+        Self {
+            tiles: HashMap::from(CLASSIC_TILES),
+        }
+    }
+}
+
+impl TileAlphabet {
+    // The tile of a character of the default alphabet, without building its map.
+    pub fn classic_tile(symbol: char) -> Option<PipeType> {
+        CLASSIC_TILES
+            .iter()
+            .find(|(classic, _)| *classic == symbol)
+            .map(|(_, pipe_type)| *pipe_type)
+    }
+
+    // An alphabet without any tile, to be filled with with_tile.
+    pub fn empty() -> Self {
+        Self {
+            tiles: HashMap::new(),
+        }
+    }
+
+    // Light and heavy box-drawing characters, as printed by Map and Renderer, with a space
+    // or a dot for the ground.
+    pub fn box_drawing() -> Self {
+        let mut alphabet = Self::empty()
+            .with_tile(' ', PipeType::Ground)
+            .with_tile('.', PipeType::Ground)
+            .with_tile('S', PipeType::StartingPosition);
+        for pipe_type in PipeType::PIPES {
+            alphabet = alphabet
+                .with_tile(pipe_type.to_box_drawing(false), pipe_type)
+                .with_tile(pipe_type.to_box_drawing(true), pipe_type);
+        }
+        alphabet
+    }
+
+    // The maps of the puzzle text mark the sectors inside and outside of the loop with I
+    // and O, they are read as ground.
+    pub fn annotated() -> Self {
+        Self::default()
+            .with_tile('I', PipeType::Ground)
+            .with_tile('O', PipeType::Ground)
+    }
+
+    // Adds a character or replaces the tile it stands for.
+    pub fn with_tile(mut self, symbol: char, pipe_type: PipeType) -> Self {
+        self.tiles.insert(symbol, pipe_type);
+        self
+    }
+
+    pub fn get(&self, symbol: char) -> Option<PipeType> {
+        self.tiles.get(&symbol).copied()
+    }
+
+    // A character standing for the tile. The first one in the alphabet order when there are
+    // several, so the result doesn't depend on the hash map.
+    pub fn symbol(&self, pipe_type: PipeType) -> Option<char> {
+        self.tiles
+            .iter()
+            .filter(|(_, tile)| **tile == pipe_type)
+            .map(|(symbol, _)| *symbol)
+            .min()
+    }
+
+    pub fn parse_tile(
+        &self,
+        symbol: char,
+        row: usize,
+        column: usize,
+    ) -> Result<PipeType, PipeTypeError> {
+        self.get(symbol)
+            .ok_or_else(|| UnknownTileError(symbol).at(row, column))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classic_alphabet() {
+        let alphabet = TileAlphabet::default();
+        assert_eq!(alphabet.get('7'), Some(PipeType::SouthWest));
+        assert_eq!(alphabet.get('O'), None);
+        for (symbol, pipe_type) in CLASSIC_TILES {
+            assert_eq!(TileAlphabet::classic_tile(symbol), Some(pipe_type));
+        }
+        assert_eq!(TileAlphabet::classic_tile('O'), None);
+        assert_eq!(
+            alphabet.parse_tile('┐', 2, 5),
+            Err(PipeTypeError {
+                symbol: '┐',
+                row: 2,
+                column: 5
+            })
+        );
+    }
+
+    #[test]
+    fn box_drawing_alphabet() {
+        let alphabet = TileAlphabet::box_drawing();
+        assert_eq!(alphabet.get('┐'), Some(PipeType::SouthWest));
+        assert_eq!(alphabet.get('┓'), Some(PipeType::SouthWest));
+        assert_eq!(alphabet.get(' '), Some(PipeType::Ground));
+        assert_eq!(alphabet.get('7'), None);
+        assert_eq!(alphabet.symbol(PipeType::Ground), Some(' '));
+    }
+
+    #[test]
+    fn annotated_alphabet() {
+        let alphabet = TileAlphabet::annotated();
+        assert_eq!(alphabet.get('I'), Some(PipeType::Ground));
+        assert_eq!(alphabet.get('O'), Some(PipeType::Ground));
+        assert_eq!(alphabet.get('F'), Some(PipeType::SouthEast));
+    }
+
+    #[test]
+    fn custom_tile() {
+        let alphabet = TileAlphabet::default().with_tile('#', PipeType::Ground);
+        assert_eq!(alphabet.get('#'), Some(PipeType::Ground));
+    }
+}
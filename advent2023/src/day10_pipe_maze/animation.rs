@@ -0,0 +1,304 @@
+use crate::day10_pipe_maze::path::{Path, RayPosition};
+use crate::day10_pipe_maze::pipe_type::PipeType;
+use crate::day10_pipe_maze::position::Position;
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::thread;
+use std::time::Duration;
+
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+const CURSOR: char = '@';
+
+// One picture of an animation: a caption and the rows of the map, north first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    pub caption: String,
+    pub lines: Vec<String>,
+}
+
+impl Display for Frame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", self.caption)?;
+        for line in &self.lines {
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+// The map in light box-drawing characters, one row of chars per line, north first.
+fn light_grid(path: &Path) -> Vec<Vec<char>> {
+    let map = path.map();
+    (0..map.get_height() as isize)
+        .rev()
+        .map(|y| {
+            (0..map.get_width() as isize)
+                .map(|x| {
+                    map.get_sector(Position(x, y))
+                        .unwrap()
+                        .pipe_type()
+                        .to_box_drawing(false)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn to_lines(grid: &[Vec<char>]) -> Vec<String> {
+    grid.iter().map(|row| row.iter().collect()).collect()
+}
+
+// The walk along the loop from S as done by Path, one frame per step. The cursor is drawn
+// with '@', the visited pipes with heavy box-drawing characters.
+pub fn traversal_frames<'a>(path: &'a Path) -> impl Iterator<Item = Frame> + 'a {
+    let map = path.map();
+    let height = map.get_height() as isize;
+    let loop_length = path.loop_length();
+    let heavy = move |position: &Position| {
+        map.get_sector(*position)
+            .unwrap()
+            .pipe_type()
+            .to_box_drawing(true)
+    };
+    let steps = path.iter().enumerate().scan(
+        (light_grid(path), None),
+        move |(grid, previous): &mut (Vec<Vec<char>>, Option<Position>), (step, position)| {
+            if let Some(previous) = previous {
+                grid[(height - 1 - previous.1) as usize][previous.0 as usize] = heavy(previous);
+            }
+            grid[(height - 1 - position.1) as usize][position.0 as usize] = CURSOR;
+            *previous = Some(*position);
+            Some(Frame {
+                caption: format!(
+                    "step {}/{} cursor {} visited {}",
+                    step,
+                    loop_length,
+                    position,
+                    step + 1
+                ),
+                lines: to_lines(grid),
+            })
+        },
+    );
+    let closed = std::iter::once_with(move || {
+        let mut grid = light_grid(path);
+        for position in path.iter() {
+            grid[(height - 1 - position.1) as usize][position.0 as usize] = heavy(position);
+        }
+        Frame {
+            caption: format!(
+                "step {}/{} back to S, the farthest pipe is {} steps away",
+                loop_length,
+                loop_length,
+                path.steps_to_be_farthest()
+            ),
+            lines: to_lines(&grid),
+        }
+    });
+    steps.chain(closed)
+}
+
+// The ray casting of Path, one frame per row from north to south. The scanned sectors off
+// the loop become I or O, and the line under the current row shows the ray after each
+// sector: I or O, or n and s while it runs along the loop and crosses it if the run ends
+// turning north or south.
+pub fn scan_frames<'a>(path: &'a Path) -> impl Iterator<Item = Frame> + 'a {
+    let map = path.map();
+    let height = map.get_height() as isize;
+    let mut grid = light_grid(path);
+    for (row, line) in grid.iter_mut().enumerate() {
+        for (x, symbol) in line.iter_mut().enumerate() {
+            let position = Position(x as isize, height - 1 - row as isize);
+            if path.contains(&position) {
+                *symbol = map
+                    .get_sector(position)
+                    .unwrap()
+                    .pipe_type()
+                    .to_box_drawing(true);
+            } else if map.get_sector(position).unwrap().pipe_type() == PipeType::Ground {
+                *symbol = '.';
+            }
+        }
+    }
+    (0..height).rev().scan(
+        (grid, 0usize),
+        move |(grid, inside_count): &mut (Vec<Vec<char>>, usize), y| {
+            let row = (height - 1 - y) as usize;
+            let states = path.scan_row(y);
+            let mut ray = String::with_capacity(states.len());
+            for (x, state) in states.iter().enumerate() {
+                let on_loop = path.contains(&Position(x as isize, y));
+                if !on_loop {
+                    grid[row][x] = if state.inside { 'I' } else { 'O' };
+                    *inside_count += state.inside as usize;
+                }
+                ray.push(match (state.ray_position, state.inside) {
+                    (RayPosition::North, _) => 'n',
+                    (RayPosition::South, _) => 's',
+                    (RayPosition::Center, true) => 'I',
+                    (RayPosition::Center, false) => 'O',
+                });
+            }
+            let mut lines: Vec<String> = Vec::with_capacity(grid.len() + 1);
+            for (index, line) in grid.iter().enumerate() {
+                let marker = if index == row { "> " } else { "  " };
+                lines.push(format!("{}{}", marker, line.iter().collect::<String>()));
+                if index == row {
+                    lines.push(format!("  {}", ray));
+                }
+            }
+            Some(Frame {
+                caption: format!("row {} inside so far {}", y, inside_count),
+                lines,
+            })
+        },
+    )
+}
+
+// Shows or saves frames, keeping one frame out of `every` plus the last one.
+#[derive(Debug, Clone)]
+pub struct Player {
+    pub delay: Duration,
+    pub every: usize,
+    pub clear: bool,
+}
+
+impl Default for Player {
+    fn default() -> Self {
+        Self {
+            delay: Duration::from_millis(100),
+            every: 1,
+            clear: true,
+        }
+    }
+}
+
+impl Player {
+    fn selected(&self, frames: impl Iterator<Item = Frame>) -> impl Iterator<Item = Frame> {
+        let every = self.every.max(1);
+        let mut frames = frames.enumerate().peekable();
+        std::iter::from_fn(move || loop {
+            let (index, frame) = frames.next()?;
+            if index % every == 0 || frames.peek().is_none() {
+                return Some(frame);
+            }
+        })
+    }
+
+    // Draws the frames one after the other in the terminal, returns how many were shown.
+    pub fn play(
+        &self,
+        frames: impl Iterator<Item = Frame>,
+        out: &mut impl io::Write,
+    ) -> io::Result<usize> {
+        let mut count = 0;
+        for frame in self.selected(frames) {
+            if self.clear {
+                write!(out, "{}", CLEAR_SCREEN)?;
+            }
+            write!(out, "{}", frame)?;
+            out.flush()?;
+            count += 1;
+            thread::sleep(self.delay);
+        }
+        Ok(count)
+    }
+
+    // Writes the frames as frame_00000.txt, frame_00001.txt... in the directory, created
+    // when missing. Returns how many were written.
+    pub fn dump(
+        &self,
+        frames: impl Iterator<Item = Frame>,
+        directory: &std::path::Path,
+    ) -> io::Result<usize> {
+        fs::create_dir_all(directory)?;
+        let mut count = 0;
+        for frame in self.selected(frames) {
+            fs::write(
+                directory.join(format!("frame_{:05}.txt", count)),
+                frame.to_string(),
+            )?;
+            count += 1;
+        }
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day10_pipe_maze::map::Map;
+
+    #[test]
+    fn traversal() {
+        let map: Map = "S7\nLJ".parse().unwrap();
+        let path = Path::new(&map).unwrap();
+        let frames: Vec<Frame> = traversal_frames(&path).collect();
+        assert_eq!(frames.len(), 5);
+        assert_eq!(frames[0].lines, vec!["@┐", "└┘"]);
+        assert_eq!(frames[0].caption, "step 0/4 cursor (0, 1) visited 1");
+        assert_eq!(frames[1].lines, vec!["┏┐", "@┘"]);
+        assert_eq!(frames[4].lines, vec!["┏┓", "┗┛"]);
+        assert_eq!(
+            frames[4].caption,
+            "step 4/4 back to S, the farthest pipe is 2 steps away"
+        );
+    }
+
+    #[test]
+    fn scan() {
+        let map: Map = ".....\n.S-7.\n.|.|.\n.L-J.\n.....".parse().unwrap();
+        let path = Path::new(&map).unwrap();
+        let frames: Vec<Frame> = scan_frames(&path).collect();
+        assert_eq!(frames.len(), 5);
+        assert_eq!(frames[1].caption, "row 3 inside so far 0");
+        assert_eq!(
+            frames[1].lines,
+            vec![
+                "  OOOOO",
+                "> O┏━┓O",
+                "  OnnOO",
+                "  .┃.┃.",
+                "  .┗━┛.",
+                "  .....",
+            ]
+        );
+        assert_eq!(frames[2].caption, "row 2 inside so far 1");
+        assert_eq!(frames[2].lines[3], "  OIIOO");
+        assert_eq!(frames[4].caption, "row 0 inside so far 1");
+    }
+
+    #[test]
+    fn play_every_other_frame() {
+        let map: Map = "S7\nLJ".parse().unwrap();
+        let path = Path::new(&map).unwrap();
+        let player = Player {
+            delay: Duration::ZERO,
+            every: 2,
+            clear: false,
+        };
+        let mut out: Vec<u8> = Vec::new();
+        // Frames 0, 2 and the last one
+        assert_eq!(player.play(traversal_frames(&path), &mut out).unwrap(), 3);
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .starts_with("step 0/4 cursor (0, 1) visited 1\n@┐\n└┘\n"));
+    }
+
+    #[test]
+    fn dump_frames() {
+        let map: Map = "S7\nLJ".parse().unwrap();
+        let path = Path::new(&map).unwrap();
+        let directory = std::env::temp_dir().join(format!("day10_frames_{}", std::process::id()));
+        let count = Player::default()
+            .dump(traversal_frames(&path), &directory)
+            .unwrap();
+        assert_eq!(count, 5);
+        assert_eq!(
+            fs::read_to_string(directory.join("frame_00004.txt")).unwrap(),
+            "step 4/4 back to S, the farthest pipe is 2 steps away\n┏┓\n┗┛\n"
+        );
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
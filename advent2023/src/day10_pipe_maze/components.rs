@@ -0,0 +1,186 @@
+use crate::day10_pipe_maze::map::Map;
+use crate::day10_pipe_maze::pipe_type::PipeType;
+use crate::day10_pipe_maze::position::Position;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ComponentKind {
+    // Every pipe is connected at both ends
+    ClosedLoop,
+    // Connected pipes with at least one loose end
+    OpenChain,
+    // A single pipe connected to nothing
+    Stray,
+}
+
+// A group of pipes linked by mutual connections.
+#[derive(Debug)]
+pub struct Component {
+    kind: ComponentKind,
+    positions: Vec<Position>,
+    // South-west and north-east corners
+    bounding_box: (Position, Position),
+    // Membership of each sector of the bounding box, row by row from the south-west corner.
+    // Kept to the box so that strays don't each carry a map-sized grid.
+    members: Vec<bool>,
+}
+
+impl Component {
+    fn new(kind: ComponentKind, positions: Vec<Position>) -> Self {
+        let south_west = Position(
+            positions.iter().map(|p| p.0).min().unwrap(),
+            positions.iter().map(|p| p.1).min().unwrap(),
+        );
+        let north_east = Position(
+            positions.iter().map(|p| p.0).max().unwrap(),
+            positions.iter().map(|p| p.1).max().unwrap(),
+        );
+        let box_size = (north_east.0 - south_west.0 + 1) * (north_east.1 - south_west.1 + 1);
+        let mut component = Component {
+            kind,
+            positions,
+            bounding_box: (south_west, north_east),
+            members: vec![false; box_size as usize],
+        };
+        for position in &component.positions {
+            let index = component
+                .member_index(position)
+                .expect("BUG: component position out of its bounding box.");
+            component.members[index] = true;
+        }
+        component
+    }
+
+    pub fn kind(&self) -> ComponentKind {
+        self.kind
+    }
+
+    pub fn size(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn positions(&self) -> &[Position] {
+        &self.positions
+    }
+
+    pub fn bounding_box(&self) -> (Position, Position) {
+        self.bounding_box
+    }
+
+    pub fn contains(&self, position: &Position) -> bool {
+        self.member_index(position)
+            .is_some_and(|index| self.members[index])
+    }
+
+    // Index of the position in members, None when it's out of the bounding box.
+    fn member_index(&self, position: &Position) -> Option<usize> {
+        let (south_west, north_east) = self.bounding_box;
+        if position.0 < south_west.0
+            || position.1 < south_west.1
+            || position.0 > north_east.0
+            || position.1 > north_east.1
+        {
+            return None;
+        }
+        let width = (north_east.0 - south_west.0 + 1) as usize;
+        Some((position.1 - south_west.1) as usize * width + (position.0 - south_west.0) as usize)
+    }
+}
+
+impl Map {
+    // Every pipe of the map grouped with the pipes it's connected to, ground excluded.
+    pub fn pipe_components(&self) -> Vec<Component> {
+        // Same order as the flat map grid
+        let mut visited = vec![false; self.get_width() * self.get_height()];
+        let index_of = |position: Position| {
+            self.position_to_index(position)
+                .expect("BUG: Trying to visit an out of bound sector.")
+        };
+        let mut components = Vec::new();
+        for y in 0..self.get_height() as isize {
+            for x in 0..self.get_width() as isize {
+                let sector = self.get_sector(Position(x, y)).unwrap();
+                let index = index_of(sector.position());
+                if sector.pipe_type() == PipeType::Ground || visited[index] {
+                    continue;
+                }
+                visited[index] = true;
+                let mut positions = Vec::new();
+                let mut fully_connected = true;
+                let mut stack = vec![sector];
+                while let Some(current) = stack.pop() {
+                    positions.push(current.position());
+                    let mut connection_count = 0;
+                    for next in self.get_connected_sectors(&current) {
+                        connection_count += 1;
+                        let next_index = index_of(next.position());
+                        if !visited[next_index] {
+                            visited[next_index] = true;
+                            stack.push(next);
+                        }
+                    }
+                    fully_connected &= connection_count == 2;
+                }
+                let kind = match (positions.len(), fully_connected) {
+                    (1, _) => ComponentKind::Stray,
+                    (_, true) => ComponentKind::ClosedLoop,
+                    (_, false) => ComponentKind::OpenChain,
+                };
+                components.push(Component::new(kind, positions));
+            }
+        }
+        components
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classify_components() {
+        let data = "S7.F-\nLJ.|.\n.-.L7";
+        let map: Map = data.parse().unwrap();
+        let components = map.pipe_components();
+        assert_eq!(components.len(), 3);
+
+        let closed_loop = components
+            .iter()
+            .find(|c| c.contains(&Position(0, 2)))
+            .unwrap();
+        assert_eq!(closed_loop.kind(), ComponentKind::ClosedLoop);
+        assert_eq!(closed_loop.size(), 4);
+        assert_eq!(closed_loop.bounding_box(), (Position(0, 1), Position(1, 2)));
+
+        let chain = components
+            .iter()
+            .find(|c| c.contains(&Position(3, 1)))
+            .unwrap();
+        assert_eq!(chain.kind(), ComponentKind::OpenChain);
+        assert_eq!(chain.size(), 5);
+        assert_eq!(chain.bounding_box(), (Position(3, 0), Position(4, 2)));
+        // Ground within the bounding box
+        assert!(!chain.contains(&Position(4, 1)));
+
+        let stray = components
+            .iter()
+            .find(|c| c.contains(&Position(1, 0)))
+            .unwrap();
+        assert_eq!(stray.kind(), ComponentKind::Stray);
+        assert_eq!(stray.bounding_box(), (Position(1, 0), Position(1, 0)));
+    }
+
+    #[test]
+    fn main_loop_is_a_component() {
+        let data = include_str!("resources/test_p2_4.txt");
+        let map: Map = data.parse().unwrap();
+        let path = crate::day10_pipe_maze::path::Path::new(&map).unwrap();
+        let start = map.get_starting_point().position();
+        let main_loop = map
+            .pipe_components()
+            .into_iter()
+            .find(|c| c.contains(&start))
+            .unwrap();
+        assert_eq!(main_loop.kind(), ComponentKind::ClosedLoop);
+        assert_eq!(main_loop.size(), path.loop_length());
+    }
+}
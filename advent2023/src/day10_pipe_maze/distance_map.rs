@@ -0,0 +1,174 @@
+use crate::day10_pipe_maze::map::{Map, MapError};
+use crate::day10_pipe_maze::position::Position;
+use std::collections::VecDeque;
+use std::io;
+
+// Shades from the closest to the farthest sector of the heat map.
+const HEAT_RAMP: [char; 9] = ['.', ':', '-', '=', '+', '*', '#', '%', '@'];
+const HEAT_COLORS: [&str; 9] = [
+    "\x1b[34m",
+    "\x1b[34m",
+    "\x1b[36m",
+    "\x1b[36m",
+    "\x1b[32m",
+    "\x1b[33m",
+    "\x1b[33m",
+    "\x1b[31m",
+    "\x1b[1;31m",
+];
+const RESET: &str = "\x1b[0m";
+
+// Steps needed to reach every sector from a source, following the pipe connections.
+#[derive(Debug)]
+pub struct DistanceMap<'a> {
+    map: &'a Map,
+    // None when the sector can't be reached, in the order of the flat map grid
+    distances: Vec<Option<usize>>,
+}
+
+impl Map {
+    // Breadth-first search from position. A move is only possible between two sectors that
+    // connect to each other, so stray pipes pointing at the network aren't reached.
+    pub fn distances_from(&self, position: Position) -> Result<DistanceMap<'_>, MapError> {
+        let source = self.get_sector(position)?;
+        let mut result = DistanceMap {
+            map: self,
+            distances: vec![None; self.get_width() * self.get_height()],
+        };
+        let mut queue = VecDeque::from([(source, 0usize)]);
+        result.set(position, 0);
+        while let Some((sector, distance)) = queue.pop_front() {
+            for next in self.get_connected_sectors(&sector) {
+                if result.get(next.position()).is_some() {
+                    continue;
+                }
+                result.set(next.position(), distance + 1);
+                queue.push_back((next, distance + 1));
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl DistanceMap<'_> {
+    pub fn get(&self, position: Position) -> Option<usize> {
+        self.distances[self.map.position_to_index(position)?]
+    }
+
+    fn set(&mut self, position: Position, distance: usize) {
+        let index = self
+            .map
+            .position_to_index(position)
+            .expect("BUG: Trying to set the distance of an out of bound sector.");
+        self.distances[index] = Some(distance);
+    }
+
+    pub fn reachable_count(&self) -> usize {
+        self.distances.iter().flatten().count()
+    }
+
+    // The farthest reachable sector and its distance.
+    pub fn farthest(&self) -> Option<(Position, usize)> {
+        self.distances
+            .iter()
+            .enumerate()
+            .filter_map(|(index, distance)| {
+                distance.map(|d| (self.map.index_to_position(index), d))
+            })
+            .max_by_key(|(_, distance)| *distance)
+    }
+
+    // One character per sector, north on top, darker when farther from the source.
+    // Unreachable sectors are left blank.
+    pub fn render_heat_map(&self, out: &mut impl io::Write, color: bool) -> io::Result<()> {
+        let max_distance = self.farthest().map_or(0, |(_, distance)| distance).max(1);
+        let width = self.map.get_width();
+        // The flat grid already starts with the north row
+        for row in self.distances.chunks(width) {
+            let mut line = String::with_capacity(width);
+            for distance in row {
+                match distance {
+                    None => line.push(' '),
+                    Some(distance) => {
+                        let shade = *distance * (HEAT_RAMP.len() - 1) / max_distance;
+                        if color {
+                            line.push_str(HEAT_COLORS[shade]);
+                            line.push(HEAT_RAMP[shade]);
+                            line.push_str(RESET);
+                        } else {
+                            line.push(HEAT_RAMP[shade]);
+                        }
+                    }
+                }
+            }
+            writeln!(out, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day10_pipe_maze::path::Path;
+
+    #[test]
+    fn distances_on_loop() {
+        let data = ".....\n.S-7.\n.|.|.\n.L-J.\n.....";
+        let map: Map = data.parse().unwrap();
+        let distances = map.distances_from(Position(1, 3)).unwrap();
+        assert_eq!(distances.get(Position(1, 3)), Some(0));
+        assert_eq!(distances.get(Position(3, 3)), Some(2));
+        assert_eq!(distances.get(Position(3, 1)), Some(4));
+        assert_eq!(distances.get(Position(2, 2)), None);
+        assert_eq!(distances.reachable_count(), 8);
+        assert_eq!(distances.farthest(), Some((Position(3, 1), 4)));
+    }
+
+    #[test]
+    fn distances_match_path() {
+        let data = include_str!("resources/input.txt");
+        let map: Map = data.parse().unwrap();
+        let path = Path::new(&map).unwrap();
+        let distances = map
+            .distances_from(map.get_starting_point().position())
+            .unwrap();
+        assert_eq!(
+            distances.farthest().map(|(_, distance)| distance),
+            Some(path.steps_to_be_farthest())
+        );
+    }
+
+    #[test]
+    fn distances_off_the_loop() {
+        // A chain that is not part of any loop
+        let data = "S-7.\n..L-";
+        let map: Map = data.parse().unwrap();
+        let distances = map.distances_from(Position(1, 1)).unwrap();
+        assert_eq!(distances.get(Position(3, 0)), Some(3));
+        assert_eq!(distances.get(Position(0, 1)), None);
+        assert_eq!(distances.reachable_count(), 4);
+    }
+
+    #[test]
+    fn distances_out_of_bounds() {
+        let map: Map = "S7\nLJ".parse().unwrap();
+        assert_eq!(
+            map.distances_from(Position(5, 5)).err(),
+            Some(MapError::OutOfBounds)
+        );
+    }
+
+    #[test]
+    fn heat_map() {
+        let data = ".....\n.S-7.\n.|.|.\n.L-J.\n.....";
+        let map: Map = data.parse().unwrap();
+        let distances = map.distances_from(Position(1, 3)).unwrap();
+        let mut out: Vec<u8> = Vec::new();
+        distances.render_heat_map(&mut out, false).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "     \n .-+ \n - # \n +#@ \n     \n"
+        );
+    }
+}
@@ -0,0 +1,222 @@
+use crate::day10_pipe_maze::direction::Direction;
+use crate::day10_pipe_maze::map::{Map, Sector};
+use crate::day10_pipe_maze::path::Path;
+use crate::day10_pipe_maze::position::Position;
+use std::collections::VecDeque;
+
+// Each sector becomes a SCALE x SCALE block with the loop pipes drawn through the block
+// centers, so the gap between two parallel pipes becomes real cells the fill can go through.
+const SCALE: isize = 3;
+
+pub struct FloodFill {
+    // Size of the upsampled grid
+    width: isize,
+    height: isize,
+    walls: Vec<bool>,
+    reached: Vec<bool>,
+    outside: Vec<Sector>,
+    inside: Vec<Sector>,
+    squeezes: Vec<(Position, Position)>,
+}
+
+impl FloodFill {
+    pub fn new(path: &Path) -> Self {
+        let map = path.map();
+        let width = map.get_width() as isize * SCALE;
+        let height = map.get_height() as isize * SCALE;
+        let mut flood_fill = FloodFill {
+            width,
+            height,
+            walls: vec![false; (width * height) as usize],
+            reached: vec![false; (width * height) as usize],
+            outside: Vec::new(),
+            inside: Vec::new(),
+            squeezes: Vec::new(),
+        };
+        flood_fill.draw_loop(path);
+        flood_fill.fill_from_border();
+        flood_fill.classify_sectors(path);
+        flood_fill.find_squeezes(path);
+        flood_fill
+    }
+
+    pub fn outside(&self) -> &[Sector] {
+        &self.outside
+    }
+
+    pub fn inside(&self) -> &[Sector] {
+        &self.inside
+    }
+
+    // Pairs of adjacent but unconnected loop sectors the fill went between.
+    pub fn squeezes(&self) -> &[(Position, Position)] {
+        &self.squeezes
+    }
+
+    // The upsampled grid, north on top: '#' for the loop, 'O' for cells reached from the
+    // border and '.' for the enclosed ones.
+    pub fn render(&self) -> String {
+        let mut result = String::with_capacity(((self.width + 1) * self.height) as usize);
+        for y in (0..self.height).rev() {
+            for x in 0..self.width {
+                let index = self.index(x, y);
+                result.push(match (self.walls[index], self.reached[index]) {
+                    (true, _) => '#',
+                    (false, true) => 'O',
+                    (false, false) => '.',
+                });
+            }
+            result.push('\n');
+        }
+        result
+    }
+
+    fn index(&self, x: isize, y: isize) -> usize {
+        (y * self.width + x) as usize
+    }
+
+    fn center(position: &Position) -> Position {
+        Position(position.0 * SCALE + 1, position.1 * SCALE + 1)
+    }
+
+    fn draw_loop(&mut self, path: &Path) {
+        for position in path.iter() {
+            let center = Self::center(position);
+            let sector = path.map().get_sector(*position).unwrap();
+            let index = self.index(center.0, center.1);
+            self.walls[index] = true;
+            for direction in sector.pipe_type().connections() {
+                let arm = center + direction.to_displacement();
+                let index = self.index(arm.0, arm.1);
+                self.walls[index] = true;
+            }
+        }
+    }
+
+    fn fill_from_border(&mut self) {
+        let mut queue: VecDeque<Position> = VecDeque::new();
+        for x in 0..self.width {
+            queue.push_back(Position(x, 0));
+            queue.push_back(Position(x, self.height - 1));
+        }
+        for y in 0..self.height {
+            queue.push_back(Position(0, y));
+            queue.push_back(Position(self.width - 1, y));
+        }
+        while let Some(cell) = queue.pop_front() {
+            let index = self.index(cell.0, cell.1);
+            if self.walls[index] || self.reached[index] {
+                continue;
+            }
+            self.reached[index] = true;
+            for direction in Direction::ALL {
+                let next = cell + direction.to_displacement();
+                if next.0 >= 0 && next.1 >= 0 && next.0 < self.width && next.1 < self.height {
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+
+    fn classify_sectors(&mut self, path: &Path) {
+        let map: &Map = path.map();
+        for y in 0..map.get_height() as isize {
+            for x in 0..map.get_width() as isize {
+                let position = Position(x, y);
+                if path.contains(&position) {
+                    continue;
+                }
+                let sector = map.get_sector(position).unwrap();
+                let center = Self::center(&position);
+                if self.reached[self.index(center.0, center.1)] {
+                    self.outside.push(sector);
+                } else {
+                    self.inside.push(sector);
+                }
+            }
+        }
+    }
+
+    fn find_squeezes(&mut self, path: &Path) {
+        let map = path.map();
+        for position in path.iter() {
+            let sector = map.get_sector(*position).unwrap();
+            // Only look east and north so every pair is seen once
+            for direction in [Direction::East, Direction::North] {
+                let neighbor = *position + direction.to_displacement();
+                if !path.contains(&neighbor) {
+                    continue;
+                }
+                if sector.pipe_type().connects(direction) {
+                    continue;
+                }
+                // The cell of this block facing the neighbor, free since they aren't connected
+                let gap = Self::center(position) + direction.to_displacement();
+                if self.reached[self.index(gap.0, gap.1)] {
+                    self.squeezes.push((*position, neighbor));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flood_fill_matches_ray_casting() {
+        let fixtures = [
+            include_str!("resources/test_p2_1.txt"),
+            include_str!("resources/test_p2_2.txt"),
+            include_str!("resources/test_p2_3.txt"),
+            include_str!("resources/test_p2_4.txt"),
+            include_str!("resources/input.txt"),
+        ];
+        for data in fixtures {
+            let map: Map = data.parse().unwrap();
+            let path = Path::new(&map).unwrap();
+            let flood_fill = FloodFill::new(&path);
+            assert_eq!(
+                flood_fill.inside().len(),
+                path.get_sectors_inside_paths().len(),
+                "Flood fill and ray casting disagree on\n{}",
+                data
+            );
+            assert_eq!(
+                flood_fill.inside().len() + flood_fill.outside().len() + path.loop_length(),
+                map.get_width() * map.get_height()
+            );
+        }
+    }
+
+    #[test]
+    fn squeeze_between_pipes() {
+        let data = include_str!("resources/test_p2_2.txt");
+        let map: Map = data.parse().unwrap();
+        let path = Path::new(&map).unwrap();
+        let flood_fill = FloodFill::new(&path);
+        // The outside goes up between the J and the L at the bottom of the loop
+        assert!(flood_fill
+            .squeezes()
+            .contains(&(Position(4, 1), Position(5, 1))));
+        assert!(flood_fill
+            .outside()
+            .iter()
+            .any(|sector| sector.position() == Position(4, 4)));
+    }
+
+    #[test]
+    fn render_upsampled() {
+        let data = "S7\nLJ";
+        let map: Map = data.parse().unwrap();
+        let path = Path::new(&map).unwrap();
+        let flood_fill = FloodFill::new(&path);
+        assert_eq!(
+            flood_fill.render(),
+            "OOOOOO\nO####O\nO#..#O\nO#..#O\nO####O\nOOOOOO\n"
+        );
+        assert!(flood_fill.inside().is_empty());
+        assert!(flood_fill.squeezes().is_empty());
+    }
+}
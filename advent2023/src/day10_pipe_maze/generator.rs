@@ -0,0 +1,424 @@
+use super::alphabet::TileAlphabet;
+use super::direction::Direction;
+use super::pipe_type::PipeType;
+use super::position::Position;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::error::Error;
+use std::fmt::Display;
+
+#[derive(Debug, PartialEq)]
+pub enum GeneratorError {
+    // A loop needs at least 2x2 sectors
+    TooSmall(usize, usize),
+    // The named share or probability isn't within [0, 1]
+    OutOfRange(&'static str, f64),
+}
+
+impl Display for GeneratorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GeneratorError::TooSmall(width, height) => write!(
+                f,
+                "A {}x{} maze is too small to hold a loop, 2x2 is the minimum.",
+                width, height
+            ),
+            GeneratorError::OutOfRange(name, value) => {
+                write!(f, "The {} must be between 0 and 1, got {}.", name, value)
+            }
+        }
+    }
+}
+
+impl Error for GeneratorError {}
+
+// Builds random mazes with a single loop through S. The loop is the outline of a random tree
+// of cells drawn on the grid lines going through the sectors, so it never touches itself and
+// the sectors inside are the grid points surrounded by cells of the tree.
+#[derive(Debug, Clone)]
+pub struct MazeGenerator {
+    pub width: usize,
+    pub height: usize,
+    pub seed: u64,
+    // Share of the possible tree nodes that are grown, the bigger the longer the loop
+    pub coverage: f64,
+    // Probability for a sector off the loop to be a random pipe instead of the ground
+    pub decoy_density: f64,
+    // Whether parallel branches of the loop can be next to each other, leaving gaps only
+    // the squeezing between pipes can go through
+    pub squeezes: bool,
+}
+
+impl Default for MazeGenerator {
+    fn default() -> Self {
+        Self {
+            width: 20,
+            height: 20,
+            seed: 0,
+            coverage: 0.5,
+            decoy_density: 0.3,
+            squeezes: true,
+        }
+    }
+}
+
+// A generated maze and what Path is expected to find in it.
+#[derive(Debug, Clone)]
+pub struct GeneratedMaze {
+    pub data: String,
+    pub start: Position,
+    pub loop_length: usize,
+    pub farthest: usize,
+    pub inside: usize,
+}
+
+// Coordinates of a tree node on the node lattice
+type Node = (usize, usize);
+
+// Cells of the tree, the cell (x, y) is the square between the sectors (x, y) and
+// (x + 1, y + 1).
+struct Cells {
+    width: usize,
+    height: usize,
+    filled: Vec<bool>,
+}
+
+impl Cells {
+    fn is_filled(&self, x: isize, y: isize) -> bool {
+        x >= 0
+            && y >= 0
+            && (x as usize) < self.width
+            && (y as usize) < self.height
+            && self.filled[y as usize * self.width + x as usize]
+    }
+
+    fn fill(&mut self, x: usize, y: usize) {
+        self.filled[y * self.width + x] = true;
+    }
+
+    // The outline of the tree going through the sector, None when it doesn't.
+    fn outline_at(&self, position: Position) -> Option<PipeType> {
+        let Position(x, y) = position;
+        let north_east = self.is_filled(x, y);
+        let north_west = self.is_filled(x - 1, y);
+        let south_west = self.is_filled(x - 1, y - 1);
+        let south_east = self.is_filled(x, y - 1);
+        // The outline leaves in a direction when the cells on both sides of it differ
+        let mut ends = Direction::ALL
+            .into_iter()
+            .filter(|direction| match direction {
+                Direction::North => north_west != north_east,
+                Direction::East => north_east != south_east,
+                Direction::South => south_west != south_east,
+                Direction::West => north_west != south_west,
+            });
+        match (ends.next(), ends.next()) {
+            (Some(first), Some(second)) => PipeType::from_directions(first, second),
+            _ => None,
+        }
+    }
+
+    fn surrounds(&self, position: Position) -> bool {
+        let Position(x, y) = position;
+        self.is_filled(x, y)
+            && self.is_filled(x - 1, y)
+            && self.is_filled(x - 1, y - 1)
+            && self.is_filled(x, y - 1)
+    }
+}
+
+impl MazeGenerator {
+    pub fn generate(&self) -> Result<GeneratedMaze, GeneratorError> {
+        if self.width < 2 || self.height < 2 {
+            return Err(GeneratorError::TooSmall(self.width, self.height));
+        }
+        for (name, value) in [
+            ("coverage", self.coverage),
+            ("decoy density", self.decoy_density),
+        ] {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(GeneratorError::OutOfRange(name, value));
+            }
+        }
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let cells = self.grow_tree(&mut rng);
+
+        let mut pipes = vec![PipeType::Ground; self.width * self.height];
+        let mut loop_positions = Vec::new();
+        let mut inside = 0;
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let position = Position(x as isize, y as isize);
+                if let Some(pipe_type) = cells.outline_at(position) {
+                    pipes[y * self.width + x] = pipe_type;
+                    loop_positions.push(position);
+                } else if cells.surrounds(position) {
+                    inside += 1;
+                }
+            }
+        }
+        let start = loop_positions[rng.gen_range(0..loop_positions.len())];
+        self.add_decoys(&mut rng, &mut pipes, start);
+
+        let alphabet = TileAlphabet::default();
+        let data = (0..self.height)
+            .rev()
+            .map(|y| {
+                (0..self.width)
+                    .map(|x| {
+                        if Position(x as isize, y as isize) == start {
+                            'S'
+                        } else {
+                            alphabet.symbol(pipes[y * self.width + x]).unwrap()
+                        }
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+        Ok(GeneratedMaze {
+            data,
+            start,
+            loop_length: loop_positions.len(),
+            farthest: loop_positions.len() / 2,
+            inside,
+        })
+    }
+
+    // Randomized Prim on a lattice of nodes, each node and each edge of the tree filling
+    // the cells between the nodes. Nodes are 2 cells apart when squeezes are allowed, so
+    // two branches can be separated by a single cell, and 3 cells apart otherwise.
+    fn grow_tree(&self, rng: &mut StdRng) -> Cells {
+        let mut cells = Cells {
+            width: self.width - 1,
+            height: self.height - 1,
+            filled: vec![false; (self.width - 1) * (self.height - 1)],
+        };
+        let stride = if self.squeezes { 2 } else { 3 };
+        let nodes = (
+            (cells.width - 1) / stride + 1,
+            (cells.height - 1) / stride + 1,
+        );
+        let target = ((nodes.0 * nodes.1) as f64 * self.coverage)
+            .round()
+            .max(1.0) as usize;
+
+        let mut frontier = Vec::new();
+        let first = (rng.gen_range(0..nodes.0), rng.gen_range(0..nodes.1));
+        Self::add_node(&mut cells, &mut frontier, first, nodes, stride);
+        let mut node_count = 1;
+        while node_count < target && !frontier.is_empty() {
+            let (from, to) = frontier.swap_remove(rng.gen_range(0..frontier.len()));
+            if cells.is_filled((to.0 * stride) as isize, (to.1 * stride) as isize) {
+                continue;
+            }
+            Self::fill_edge(&mut cells, from, to, stride);
+            Self::add_node(&mut cells, &mut frontier, to, nodes, stride);
+            node_count += 1;
+        }
+        cells
+    }
+
+    fn add_node(
+        cells: &mut Cells,
+        frontier: &mut Vec<(Node, Node)>,
+        node: Node,
+        nodes: (usize, usize),
+        stride: usize,
+    ) {
+        cells.fill(node.0 * stride, node.1 * stride);
+        for direction in Direction::ALL {
+            let displacement = direction.to_displacement();
+            let x = node.0 as isize + displacement.0;
+            let y = node.1 as isize + displacement.1;
+            if x >= 0
+                && y >= 0
+                && (x as usize) < nodes.0
+                && (y as usize) < nodes.1
+                && !cells.is_filled(x * stride as isize, y * stride as isize)
+            {
+                frontier.push((node, (x as usize, y as usize)));
+            }
+        }
+    }
+
+    fn fill_edge(cells: &mut Cells, from: Node, to: Node, stride: usize) {
+        let (low, high) = if from <= to { (from, to) } else { (to, from) };
+        for step in 1..stride {
+            if low.0 == high.0 {
+                cells.fill(low.0 * stride, low.1 * stride + step);
+            } else {
+                cells.fill(low.0 * stride + step, low.1 * stride);
+            }
+        }
+    }
+
+    // Random pipes off the loop. None of them points at S so its type can still be inferred.
+    fn add_decoys(&self, rng: &mut StdRng, pipes: &mut [PipeType], start: Position) {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let index = y * self.width + x;
+                if pipes[index] != PipeType::Ground || !rng.gen_bool(self.decoy_density) {
+                    continue;
+                }
+                let pipe_type = PipeType::PIPES[rng.gen_range(0..PipeType::PIPES.len())];
+                let position = Position(x as isize, y as isize);
+                let points_at_start = pipe_type
+                    .connections()
+                    .iter()
+                    .any(|direction| position + direction.to_displacement() == start);
+                if !points_at_start {
+                    pipes[index] = pipe_type;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day10_pipe_maze::flood_fill::FloodFill;
+    use crate::day10_pipe_maze::map::Map;
+    use crate::day10_pipe_maze::path::Path;
+
+    fn check(generator: &MazeGenerator) {
+        let maze = generator.generate().unwrap();
+        let map: Map = maze.data.parse().unwrap();
+        let path = Path::new(&map).unwrap_or_else(|err| panic!("{} in\n{}", err, maze.data));
+        assert_eq!(map.get_starting_point().position(), maze.start);
+        assert_eq!(path.loop_length(), maze.loop_length, "{:?}", generator);
+        assert_eq!(
+            path.steps_to_be_farthest(),
+            maze.farthest,
+            "{:?}",
+            generator
+        );
+        assert_eq!(
+            path.get_sectors_inside_paths().len(),
+            maze.inside,
+            "{:?}\n{}",
+            generator,
+            maze.data
+        );
+        assert_eq!(path.count_sectors_inside_path(), maze.inside);
+        assert_eq!(FloodFill::new(&path).inside().len(), maze.inside);
+    }
+
+    #[test]
+    fn generated_mazes_match_expectations() {
+        for seed in 0..200 {
+            for squeezes in [true, false] {
+                check(&MazeGenerator {
+                    width: 2 + (seed as usize * 7) % 40,
+                    height: 2 + (seed as usize * 13) % 40,
+                    seed,
+                    coverage: (seed % 10) as f64 / 10.0 + 0.1,
+                    decoy_density: (seed % 4) as f64 / 4.0,
+                    squeezes,
+                });
+            }
+        }
+    }
+
+    #[test]
+    fn same_seed_same_maze() {
+        let generator = MazeGenerator {
+            seed: 42,
+            ..Default::default()
+        };
+        assert_eq!(
+            generator.generate().unwrap().data,
+            generator.generate().unwrap().data
+        );
+        let other = MazeGenerator {
+            seed: 43,
+            ..Default::default()
+        };
+        assert_ne!(
+            generator.generate().unwrap().data,
+            other.generate().unwrap().data
+        );
+    }
+
+    #[test]
+    fn smallest_maze() {
+        let maze = MazeGenerator {
+            width: 2,
+            height: 2,
+            ..Default::default()
+        }
+        .generate()
+        .unwrap();
+        assert_eq!(maze.loop_length, 4);
+        assert_eq!(maze.inside, 0);
+        assert_eq!(
+            MazeGenerator {
+                width: 1,
+                ..Default::default()
+            }
+            .generate()
+            .err(),
+            Some(GeneratorError::TooSmall(1, 20))
+        );
+    }
+
+    #[test]
+    fn ratios_out_of_range() {
+        assert_eq!(
+            MazeGenerator {
+                coverage: 1.5,
+                ..Default::default()
+            }
+            .generate()
+            .err(),
+            Some(GeneratorError::OutOfRange("coverage", 1.5))
+        );
+        assert_eq!(
+            MazeGenerator {
+                decoy_density: -0.1,
+                ..Default::default()
+            }
+            .generate()
+            .err(),
+            Some(GeneratorError::OutOfRange("decoy density", -0.1))
+        );
+        assert!(MazeGenerator {
+            coverage: f64::NAN,
+            ..Default::default()
+        }
+        .generate()
+        .is_err());
+        assert!(MazeGenerator {
+            coverage: 0.0,
+            decoy_density: 1.0,
+            ..Default::default()
+        }
+        .generate()
+        .is_ok());
+    }
+
+    #[test]
+    fn squeezes_between_branches() {
+        let generator = MazeGenerator {
+            width: 30,
+            height: 30,
+            coverage: 1.0,
+            ..Default::default()
+        };
+        let maze = generator.generate().unwrap();
+        let map: Map = maze.data.parse().unwrap();
+        let path = Path::new(&map).unwrap();
+        assert!(!FloodFill::new(&path).squeezes().is_empty());
+
+        let maze = MazeGenerator {
+            squeezes: false,
+            ..generator
+        }
+        .generate()
+        .unwrap();
+        let map: Map = maze.data.parse().unwrap();
+        let path = Path::new(&map).unwrap();
+        assert!(FloodFill::new(&path).squeezes().is_empty());
+    }
+}
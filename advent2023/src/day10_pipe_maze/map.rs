@@ -1,9 +1,11 @@
-use super::pipe_type::{InvalidPositionError, PipeType};
-use super::position::{Displacement, Position};
-use std::collections::HashMap;
+use super::alphabet::TileAlphabet;
+use super::direction::Direction;
+use super::pipe_type::{PipeType, PipeTypeError};
+use super::position::Position;
 use std::fmt::Display;
 use std::str::FromStr;
 
+// A view on one cell of the map, the map itself only stores the pipe types.
 #[derive(Debug, PartialEq, Eq, Clone, Hash, Copy)]
 pub struct Sector {
     position: Position,
@@ -14,6 +16,16 @@ impl Sector {
     pub fn pipe_type(&self) -> PipeType {
         self.pipe_type
     }
+
+    pub fn position(&self) -> Position {
+        self.position
+    }
+}
+
+impl Display for Sector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.pipe_type)
+    }
 }
 
 // TODO see to use thiserror/anyhow/eyre ?
@@ -21,7 +33,7 @@ impl Sector {
 pub enum ParseMapError {
     EmptyString,
     LineLength(usize),
-    PositionError(InvalidPositionError),
+    PipeTypeError(PipeTypeError),
     MultipleStartingPositions(Position),
     NoStartingPosition,
 }
@@ -31,7 +43,7 @@ impl Display for ParseMapError {
         match self {
             ParseMapError::EmptyString => write!(f, "The source string is empty. Nothing to parse."),
             ParseMapError::LineLength(line) => write!(f, "Invalid line length for line {} of the source string.", line),
-            ParseMapError::PositionError(err) => write!(f, "Invalid position error {}", err),
+            ParseMapError::PipeTypeError(err) => write!(f, "{}", err),
             ParseMapError::MultipleStartingPositions(pos) => write!(f, "Multiple starting positions are defined in the source string. Fist one defined: {}", pos),
             ParseMapError::NoStartingPosition => write!(f, "No starting position defined in the source string."),
         }
@@ -40,7 +52,7 @@ impl Display for ParseMapError {
 impl std::error::Error for ParseMapError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
-            Self::PositionError(e) => Some(e),
+            Self::PipeTypeError(e) => Some(e),
             _ => None,
         }
     }
@@ -53,40 +65,59 @@ pub enum MapError {
 
 #[derive(Debug)]
 pub struct Map {
-    // TODO why not a HashMap Position => sector instead?
-    positions: Box<[Box<[Sector]>]>,
+    // Row by row, northernmost row first like in the source string
+    pipes: Vec<PipeType>,
+    width: usize,
+    height: usize,
     starting_position: Position,
 }
 
+// The maze redrawn with box-drawing characters, north on top.
+impl Display for Map {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.pipes.chunks(self.width) {
+            for pipe_type in row {
+                write!(f, "{}", pipe_type)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
 // TODO Should that be a different object like a Factory or something?
 impl FromStr for Map {
     type Err = ParseMapError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Map::parse_with(s, &TileAlphabet::default())
+    }
+}
+
+impl Map {
+    // Reads a map written with another set of characters than the puzzle input.
+    pub fn parse_with(s: &str, alphabet: &TileAlphabet) -> Result<Map, ParseMapError> {
         let trimmed = s.trim();
         if trimmed.is_empty() {
             return Err(ParseMapError::EmptyString);
         }
-        let width = s.lines().next().unwrap().len();
+        let width = s.lines().next().unwrap().chars().count();
         let height = s.lines().count();
 
-        let mut sector_rows: Vec<Box<[Sector]>> = Vec::with_capacity(height);
+        let mut pipes: Vec<PipeType> = Vec::with_capacity(width * height);
 
         let mut starting_position: Option<Position> = None;
         for (y, line) in s.lines().enumerate() {
             if line.chars().count() != width {
                 return Err(ParseMapError::LineLength(y));
             }
-            let mut sector_row: Vec<Sector> = Vec::with_capacity(width);
             for (x, p) in line.chars().enumerate() {
-                let pipe_type = PipeType::try_from(p).map_err(ParseMapError::PositionError)?;
-                let position = Position(x as isize, (height - 1 - y) as isize);
-                let sector = Sector {
-                    position,
-                    pipe_type,
-                };
-                sector_row.push(sector);
+                let pipe_type = alphabet
+                    .parse_tile(p, y, x)
+                    .map_err(ParseMapError::PipeTypeError)?;
+                pipes.push(pipe_type);
                 if pipe_type == PipeType::StartingPosition {
+                    let position = Position(x as isize, (height - 1 - y) as isize);
                     match starting_position {
                         None => starting_position = Some(position),
                         Some(coords) => {
@@ -95,11 +126,12 @@ impl FromStr for Map {
                     }
                 }
             }
-            sector_rows.push(sector_row.into_boxed_slice());
         }
         let mut result = match starting_position {
             Some(start_coords) => Map {
-                positions: sector_rows.into_boxed_slice(),
+                pipes,
+                width,
+                height,
                 starting_position: start_coords,
             },
             None => return Err(ParseMapError::NoStartingPosition),
@@ -110,108 +142,111 @@ impl FromStr for Map {
 }
 
 impl Map {
+    // Which of the four neighbors of the starting point connect back to it.
+    pub fn get_starting_point_connections(&self) -> Vec<Direction> {
+        Direction::ALL
+            .into_iter()
+            .filter(|direction| {
+                self.get_sector(self.starting_position + direction.to_displacement())
+                    .is_ok_and(|sector| sector.pipe_type.connects(direction.opposite()))
+            })
+            .collect()
+    }
+
+    pub fn count_starting_point_connections(&self) -> usize {
+        self.get_starting_point_connections().len()
+    }
+
+    // The starting point keeps the StartingPosition type when it isn't connected to exactly
+    // two neighbors, Path reports why it can't be part of a loop.
     fn infer_starting_point_type(&mut self) {
-        let starting_point_position = self.starting_position;
-        let starting_point_sector = self.get_sector(starting_point_position).unwrap();
-        let tests: HashMap<&str, Displacement> = HashMap::from([
-            ("east", Displacement(1, 0)),
-            ("west", Displacement(-1, 0)),
-            ("north", Displacement(0, 1)),
-            ("south", Displacement(0, -1)),
-        ]);
-        let mut results: HashMap<&str, bool> = HashMap::from([
-            ("east", false),
-            ("west", false),
-            ("north", false),
-            ("south", false),
-        ]);
-        for (direction, dis) in tests {
-            let cobaye_sector = self.get_sector(starting_point_position + dis);
-            match cobaye_sector {
-                Ok(sector) => {
-                    if self
-                        .get_next_sectors(sector)
-                        .contains(&starting_point_sector)
-                    {
-                        results.entry(direction).and_modify(|b| *b = true);
-                    }
-                }
-                Err(_) => continue,
-            }
+        if let [first, second] = self.get_starting_point_connections()[..] {
+            let index = self.position_to_index(self.starting_position).unwrap();
+            self.pipes[index] = PipeType::from_directions(first, second).unwrap();
         }
-        let pipe_type: PipeType;
-        // NorthSouth
-        if results["north"] && results["south"] {
-            pipe_type = PipeType::NorthSouth;
-        } else if results["east"] && results["west"] {
-            pipe_type = PipeType::EastWest;
-        } else if results["north"] && results["east"] {
-            pipe_type = PipeType::NorthEast;
-        } else if results["north"] && results["west"] {
-            pipe_type = PipeType::NorthWest;
-        } else if results["south"] && results["west"] {
-            pipe_type = PipeType::SouthWest;
-        } else if results["south"] && results["east"] {
-            pipe_type = PipeType::SouthEast;
-        } else {
-            panic!("Starting point not connected to two neighbors")
-        }
-        self.get_sector_mut(starting_point_position)
-            .unwrap()
-            .pipe_type = pipe_type;
     }
 
-    pub fn get_starting_point(&self) -> &Sector {
+    pub fn get_starting_point(&self) -> Sector {
         self.get_sector(self.starting_position).unwrap()
     }
 
-    // return two indexes that can be used in positions[a][b]
-    fn position_to_index(&self, position: &Position) -> (usize, usize) {
-        (
-            self.get_height() - 1 - position.1 as usize,
-            position.0 as usize,
+    // Index of the position in the flat grid, None when it's out of the map.
+    pub fn position_to_index(&self, position: Position) -> Option<usize> {
+        if !self.is_position_valid(&position) {
+            return None;
+        }
+        Some((self.height - 1 - position.1 as usize) * self.width + position.0 as usize)
+    }
+
+    pub fn index_to_position(&self, index: usize) -> Position {
+        Position(
+            (index % self.width) as isize,
+            (self.height - 1 - index / self.width) as isize,
         )
     }
 
     fn is_position_valid(&self, position: &Position) -> bool {
         !(position.0 < 0
             || position.1 < 0
-            || position.0 >= self.get_width() as isize
-            || position.1 >= self.get_height() as isize)
+            || position.0 >= self.width as isize
+            || position.1 >= self.height as isize)
     }
-    pub fn get_sector(&self, position: Position) -> Result<&Sector, MapError> {
-        if !self.is_position_valid(&position) {
-            return Err(MapError::OutOfBounds);
-        }
-        let (a, b) = self.position_to_index(&position);
-        Ok(&self.positions[a][b])
+
+    pub fn get_sector(&self, position: Position) -> Result<Sector, MapError> {
+        let index = self
+            .position_to_index(position)
+            .ok_or(MapError::OutOfBounds)?;
+        Ok(Sector {
+            position,
+            pipe_type: self.pipes[index],
+        })
     }
 
-    pub fn get_sector_mut(&mut self, position: Position) -> Result<&mut Sector, MapError> {
-        if !self.is_position_valid(&position) {
-            return Err(MapError::OutOfBounds);
-        }
-        let (a, b) = self.position_to_index(&position);
-        Ok(&mut self.positions[a][b])
+    // Every sector of the map, northernmost row first.
+    pub fn sectors(&self) -> impl Iterator<Item = Sector> + '_ {
+        self.pipes
+            .iter()
+            .enumerate()
+            .map(|(index, pipe_type)| Sector {
+                position: self.index_to_position(index),
+                pipe_type: *pipe_type,
+            })
     }
 
     pub fn get_width(&self) -> usize {
-        self.positions.first().unwrap().len()
+        self.width
     }
 
     pub fn get_height(&self) -> usize {
-        self.positions.len()
+        self.height
     }
 
-    pub fn get_next_sectors(&self, sector: &Sector) -> Vec<&Sector> {
-        let mut result: Vec<&Sector> = Vec::new();
-        for displacement in sector.pipe_type.get_valid_connections() {
-            match self.get_sector(sector.position + displacement) {
-                Ok(sector) => result.push(sector),
-                Err(_) => continue,
-            }
-        }
-        result
+    pub fn get_next_sectors<'a>(&'a self, sector: &Sector) -> impl Iterator<Item = Sector> + 'a {
+        let position = sector.position;
+        sector
+            .pipe_type
+            .connections()
+            .iter()
+            .filter_map(move |direction| {
+                self.get_sector(position + direction.to_displacement()).ok()
+            })
+    }
+
+    // Neighbors the sector connects to and that connect back to it.
+    pub fn get_connected_sectors<'a>(
+        &'a self,
+        sector: &Sector,
+    ) -> impl Iterator<Item = Sector> + 'a {
+        let position = sector.position;
+        sector
+            .pipe_type
+            .connections()
+            .iter()
+            .filter_map(move |direction| {
+                self.get_sector(position + direction.to_displacement())
+                    .ok()
+                    .filter(|next| next.pipe_type.connects(direction.opposite()))
+            })
     }
 }
 
@@ -223,7 +258,7 @@ mod tests {
     fn map_from_str() {
         let char_map = "JJJ\n|||\nFFF\n-S-";
         let map: Map = char_map.parse().unwrap();
-        assert_eq!(map.positions[0][1].pipe_type, PipeType::NorthWest);
+        assert_eq!(map.pipes[1], PipeType::NorthWest);
     }
 
     #[test]
@@ -253,11 +288,34 @@ mod tests {
         let map = char_map.parse::<Map>();
         assert!(map.is_err());
         match map {
-            Err(ParseMapError::PositionError(InvalidPositionError('!'))) => (),
+            Err(ParseMapError::PipeTypeError(PipeTypeError {
+                symbol: '!',
+                row: 2,
+                column: 1,
+            })) => (),
             _ => panic!(),
         }
     }
 
+    #[test]
+    fn map_from_box_drawing() {
+        let char_map = "┏━┓\n┃ ┃\n┗S┛";
+        let map = Map::parse_with(char_map, &TileAlphabet::box_drawing()).unwrap();
+        assert_eq!(map.get_width(), 3);
+        assert_eq!(map.to_string(), "┌─┐\n│ │\n└─┘\n");
+    }
+
+    #[test]
+    fn map_from_annotated_str() {
+        let char_map = "OOOO\nOS7O\nOLJO\nOOII";
+        assert!(char_map.parse::<Map>().is_err());
+        let map = Map::parse_with(char_map, &TileAlphabet::annotated()).unwrap();
+        assert_eq!(
+            map.get_sector(Position(3, 0)).unwrap().pipe_type(),
+            PipeType::Ground
+        );
+    }
+
     #[test]
     fn map_from_str_no_starting_point() {
         let char_map = "JJJ\n|||\nFFF\n---";
@@ -285,7 +343,9 @@ mod tests {
     fn test_get_next_coordinates() {
         let char_map = "F-7\n|.|\n|.|\nLSJ";
         let map = char_map.parse::<Map>().unwrap();
-        let next_sectors = map.get_next_sectors(map.get_sector(Position(0, 0)).unwrap());
+        let next_sectors: Vec<Sector> = map
+            .get_next_sectors(&map.get_sector(Position(0, 0)).unwrap())
+            .collect();
         assert_eq!(next_sectors.len(), 2);
         assert!(next_sectors.contains(&map.get_sector(Position(0, 1)).unwrap()));
         assert!(next_sectors.contains(&map.get_sector(Position(1, 0)).unwrap()));
@@ -296,15 +356,18 @@ mod tests {
         let char_map = "F-7\n|.|\n|.|\nLSJ";
         let map = char_map.parse::<Map>().unwrap();
         assert!(map
-            .get_next_sectors(map.get_sector(Position(1, 1)).unwrap())
-            .is_empty());
+            .get_next_sectors(&map.get_sector(Position(1, 1)).unwrap())
+            .next()
+            .is_none());
     }
 
     #[test]
     fn test_get_next_coordinates_starting_position() {
         let char_map = "F-7\n|.|\n|.|\nLSJ";
         let map = char_map.parse::<Map>().unwrap();
-        let next_sectors = map.get_next_sectors(map.get_sector(Position(1, 0)).unwrap());
+        let next_sectors: Vec<Sector> = map
+            .get_next_sectors(&map.get_sector(Position(1, 0)).unwrap())
+            .collect();
         assert_eq!(next_sectors.len(), 2);
         assert!(next_sectors.contains(&map.get_sector(Position(0, 0)).unwrap()));
         assert!(next_sectors.contains(&map.get_sector(Position(2, 0)).unwrap()));
@@ -316,7 +379,7 @@ mod tests {
         let map = char_map.parse::<Map>().unwrap();
         assert_eq!(
             map.get_sector(Position(0, 0)).unwrap(),
-            &Sector {
+            Sector {
                 position: Position(0, 0),
                 pipe_type: PipeType::NorthEast
             }
@@ -336,10 +399,59 @@ mod tests {
         let map = char_map.parse::<Map>().unwrap();
         assert_eq!(
             map.get_starting_point(),
-            &Sector {
+            Sector {
                 position: Position(1, 0),
                 pipe_type: PipeType::EastWest
             }
         );
     }
+
+    #[test]
+    fn test_infer_starting_point_not_connected() {
+        let char_map = ".|.\n-S-\n.|.";
+        let map = char_map.parse::<Map>().unwrap();
+        assert_eq!(
+            map.get_starting_point().pipe_type(),
+            PipeType::StartingPosition
+        );
+        assert_eq!(map.count_starting_point_connections(), 4);
+    }
+
+    #[test]
+    fn test_starting_point_connections() {
+        let char_map = "F-7\n|.|\n|.|\nLSJ";
+        let map = char_map.parse::<Map>().unwrap();
+        assert_eq!(
+            map.get_starting_point_connections(),
+            vec![Direction::East, Direction::West]
+        );
+    }
+
+    #[test]
+    fn test_display_map() {
+        let char_map = "F-7\n|.|\n|.|\nLSJ";
+        let map = char_map.parse::<Map>().unwrap();
+        assert_eq!(map.to_string(), "┌─┐\n│ │\n│ │\n└─┘\n");
+    }
+
+    #[test]
+    fn test_get_connected_sectors() {
+        let char_map = "F-7\n|.|\n|.-\nLSJ";
+        let map = char_map.parse::<Map>().unwrap();
+        let connected: Vec<Sector> = map
+            .get_connected_sectors(&map.get_sector(Position(2, 0)).unwrap())
+            .collect();
+        assert_eq!(connected, vec![map.get_sector(Position(1, 0)).unwrap()]);
+    }
+
+    #[test]
+    fn test_position_index_conversion() {
+        let char_map = "F-7\n|.|\n|.|\nLSJ";
+        let map = char_map.parse::<Map>().unwrap();
+        assert_eq!(map.position_to_index(Position(0, 3)), Some(0));
+        assert_eq!(map.position_to_index(Position(1, 0)), Some(10));
+        assert_eq!(map.position_to_index(Position(3, 0)), None);
+        assert_eq!(map.index_to_position(10), Position(1, 0));
+        assert_eq!(map.sectors().count(), 12);
+    }
 }
@@ -1,7 +1,15 @@
-mod map;
-mod path;
-mod pipe_type;
-mod position;
+pub mod alphabet;
+pub mod animation;
+pub mod components;
+pub mod direction;
+pub mod distance_map;
+pub mod flood_fill;
+pub mod generator;
+pub mod map;
+pub mod path;
+pub mod pipe_type;
+pub mod position;
+pub mod render;
 mod services;
 
 pub fn entrypoint() -> Result<String, String> {
@@ -14,3 +22,8 @@ pub fn entrypoint() -> Result<String, String> {
         (Err(x1), Err(x2)) => Err(format!("ERROR ON ALL PARTS: {} {}", x1, x2)),
     }
 }
+
+// Replays the loop traversal or the inside scan frame by frame, see services::animate.
+pub fn animate(args: &[String]) -> Result<(), String> {
+    services::animate(args)
+}
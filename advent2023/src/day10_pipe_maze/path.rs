@@ -1,107 +1,251 @@
 use crate::day10_pipe_maze::map::{Map, Sector};
 use crate::day10_pipe_maze::pipe_type::PipeType;
 use crate::day10_pipe_maze::position::Position;
-use std::collections::HashSet;
+use crate::day10_pipe_maze::render::Renderer;
+use std::fmt::Display;
 
-#[derive(Eq, PartialEq, Copy, Clone)]
-enum RayPosition {
+// Where the ray runs while it goes along a horizontal part of the loop.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum RayPosition {
     North,
     Center,
     South,
 }
 
+// The ray scanning a row from the west, right after a sector.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct RayState {
+    pub inside: bool,
+    pub ray_position: RayPosition,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PathError {
+    DeadEnd(Position),
+    AmbiguousStart(usize),
+    BrokenLoop(Position),
+}
+
+impl Display for PathError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PathError::DeadEnd(pos) => write!(f, "The pipe at {} leads nowhere, the loop can't be closed.", pos),
+            PathError::AmbiguousStart(connections) => write!(f, "The starting point is connected to {} neighbors, can't tell which two belong to the loop.", connections),
+            PathError::BrokenLoop(pos) => write!(f, "The pipe at {} doesn't connect back to the previous pipe of the loop.", pos),
+        }
+    }
+}
+
+impl std::error::Error for PathError {}
+
+// Marks the sectors off the loop in Path::indexes.
+const NOT_ON_LOOP: u32 = u32::MAX;
+
 pub struct Path<'a> {
     map: &'a Map,
-    path: HashSet<Sector>,
+    // The loop in traversal order, starting at S
+    path: Vec<Position>,
+    // Index in path of each sector of the flat map grid, NOT_ON_LOOP when it's off the loop.
+    // Also used for membership.
+    indexes: Vec<u32>,
 }
 
 impl<'a> Path<'a> {
-    pub fn new(map: &'a Map) -> Self {
-        Path {
-            map,
-            path: Self::find_path(map),
+    pub fn new(map: &'a Map) -> Result<Self, PathError> {
+        let path = Self::find_path(map)?;
+        let mut indexes = vec![NOT_ON_LOOP; map.get_width() * map.get_height()];
+        for (index, position) in path.iter().enumerate() {
+            let map_index = map
+                .position_to_index(*position)
+                .expect("BUG: loop position out of the map.");
+            indexes[map_index] = index as u32;
         }
+        Ok(Path { map, path, indexes })
+    }
+
+    // Index of the position in path.
+    fn loop_index(&self, position: &Position) -> Option<usize> {
+        let index = self.indexes[self.map.position_to_index(*position)?];
+        (index != NOT_ON_LOOP).then_some(index as usize)
+    }
+
+    pub fn map(&self) -> &'a Map {
+        self.map
+    }
+
+    pub fn contains(&self, position: &Position) -> bool {
+        self.loop_index(position).is_some()
+    }
+
+    pub fn loop_length(&self) -> usize {
+        self.path.len()
+    }
+
+    // Steps needed to reach a loop position from S, going forward and going backward.
+    pub fn steps_from_start(&self, position: &Position) -> Option<(usize, usize)> {
+        let index = self.loop_index(position)?;
+        Some((index, (self.path.len() - index) % self.path.len()))
+    }
+
+    pub fn distance_from_start(&self, position: &Position) -> Option<usize> {
+        self.steps_from_start(position)
+            .map(|(forward, backward)| forward.min(backward))
+    }
+
+    // A loop on a grid always has an even length, so there's a single farthest sector.
+    pub fn farthest_sector(&self) -> Sector {
+        let position = self.path[self.path.len() / 2];
+        self.map
+            .get_sector(position)
+            .expect("BUG: loop position out of the map.")
     }
 
     pub fn steps_to_be_farthest(&self) -> usize {
-        self.path.len() / 2
+        self.distance_from_start(&self.farthest_sector().position())
+            .unwrap()
+    }
+
+    // The loop from S, in traversal order.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &Position> {
+        self.path.iter()
+    }
+
+    // The loop from S, in the opposite direction.
+    pub fn iter_rev(&self) -> impl Iterator<Item = &Position> {
+        self.path[..1].iter().chain(self.path[1..].iter().rev())
     }
 
     pub fn get_sectors_inside_paths(&self) -> Vec<Sector> {
         let mut inside_sectors = Vec::new();
         for y in 0..self.map.get_height() as isize {
-            let mut inside: bool = false;
-            let mut ray_position = RayPosition::Center;
-            for x in 0..self.map.get_width() as isize {
+            for (x, state) in self.scan_row(y).into_iter().enumerate() {
+                let position = Position(x as isize, y);
+                // odd => within the path
+                if state.inside && !self.contains(&position) {
+                    inside_sectors.push(
+                        self.map
+                            .get_sector(position)
+                            .expect("BUG: Trying to get out of bound sector."),
+                    );
+                }
+            }
+        }
+        inside_sectors
+    }
+
+    // The state of the ray after each sector of the row, west to east.
+    pub fn scan_row(&self, y: isize) -> Vec<RayState> {
+        let width = self.map.get_width();
+        let row_start = self
+            .map
+            .position_to_index(Position(0, y))
+            .expect("BUG: Trying to scan an out of bound row.");
+        let mut states = Vec::with_capacity(width);
+        let mut inside: bool = false;
+        let mut ray_position = RayPosition::Center;
+        let row = &self.indexes[row_start..row_start + width];
+        for (x, index) in row.iter().enumerate() {
+            // the sector on a barrier
+            if *index != NOT_ON_LOOP {
                 let sector_under_analysis = self
                     .map
-                    .get_sector(Position(x, y))
+                    .get_sector(Position(x as isize, y))
                     .expect("BUG: Trying to get out of bound sector.");
-                // the sector on a barrier
-                if self.path.contains(sector_under_analysis) {
-                    // TODO Probably buggy what about corners
-                    match sector_under_analysis.pipe_type() {
-                        PipeType::NorthSouth => {
-                            inside = !inside;
-                            ray_position = RayPosition::Center;
-                        }
-                        PipeType::SouthWest if ray_position == RayPosition::South => {
-                            inside = !inside;
-                            ray_position = RayPosition::Center;
-                        }
-                        PipeType::NorthWest if ray_position == RayPosition::North => {
-                            inside = !inside;
-                            ray_position = RayPosition::Center;
-                        }
-                        PipeType::NorthEast => ray_position = RayPosition::South,
-                        PipeType::SouthEast => ray_position = RayPosition::North,
-                        _ => (),
+                // Corners: L and F start a horizontal run of the loop, ray_position keeps the side
+                // the ray goes by, south of an L and north of an F. The closing 7 or J crosses
+                // the ray when it heads to that side, as in L-7 or F-J. L-J and F-7 only touch it.
+                match sector_under_analysis.pipe_type() {
+                    PipeType::NorthSouth => {
+                        inside = !inside;
+                        ray_position = RayPosition::Center;
                     }
-                    continue;
-                }
-                // odd => within the path
-                if inside {
-                    inside_sectors.push(*sector_under_analysis);
+                    PipeType::SouthWest if ray_position == RayPosition::South => {
+                        inside = !inside;
+                        ray_position = RayPosition::Center;
+                    }
+                    PipeType::NorthWest if ray_position == RayPosition::North => {
+                        inside = !inside;
+                        ray_position = RayPosition::Center;
+                    }
+                    // the run ends on the side it started from, no crossing
+                    PipeType::SouthWest | PipeType::NorthWest => ray_position = RayPosition::Center,
+                    PipeType::NorthEast => ray_position = RayPosition::South,
+                    PipeType::SouthEast => ray_position = RayPosition::North,
+                    _ => (),
                 }
             }
+            states.push(RayState {
+                inside,
+                ray_position,
+            });
         }
-        inside_sectors
+        states
     }
 
-    fn find_path(map: &Map) -> HashSet<Sector> {
-        let mut path = HashSet::new();
-        let starting_point = map.get_starting_point();
-        let neighbors = map.get_next_sectors(starting_point);
+    // Number of sectors enclosed by the loop, from its vertices only, in O(loop length).
+    // The shoelace formula gives the area of the polygon going through the centers of the
+    // loop sectors, then Pick's theorem (A = I + B / 2 - 1) gives the interior points I from
+    // the area A and the boundary points B, which are the loop sectors themselves.
+    pub fn count_sectors_inside_path(&self) -> usize {
+        let twice_area: isize = self
+            .path
+            .iter()
+            .zip(self.path.iter().cycle().skip(1))
+            .map(|(current, next)| current.0 * next.1 - next.0 * current.1)
+            .sum();
+        let boundary = self.path.len() as isize;
+        ((twice_area.abs() - boundary) / 2 + 1) as usize
+    }
 
-        if neighbors.len() != 2 {
-            panic!(
-                "Starting point must have exactly 2 neighbors, found {}",
-                neighbors.len()
-            );
+    fn find_path(map: &Map) -> Result<Vec<Position>, PathError> {
+        let starting_point = map.get_starting_point();
+        // The type stays unknown when the start isn't connected to exactly two neighbors
+        if starting_point.pipe_type() == PipeType::StartingPosition {
+            return match map.count_starting_point_connections() {
+                connections if connections > 2 => Err(PathError::AmbiguousStart(connections)),
+                _ => Err(PathError::DeadEnd(starting_point.position())),
+            };
         }
 
-        path.insert(*starting_point);
-        let mut cursor = neighbors[0];
-        path.insert(*cursor);
-
-        if neighbors.len() != 2 {
-            panic!(
-                "All points in path must have exactly 2 neighbors, found {} at {:?}",
-                neighbors.len(),
-                cursor
-            );
+        let mut path = vec![starting_point.position()];
+        let mut previous = starting_point;
+        let mut cursor = map.get_next_sectors(&starting_point).next().unwrap();
+        while cursor != starting_point {
+            // a pipe has at most two ends and the previous one must be one of them, so the
+            // walk can't come back to a pipe before reaching the start again
+            let mut neighbors = map.get_next_sectors(&cursor);
+            let (first, second) = (neighbors.next(), neighbors.next());
+            let next = match (first, second) {
+                // entered the ground
+                (None, _) => return Err(PathError::DeadEnd(previous.position())),
+                (Some(first), second) if first != previous && second != Some(previous) => {
+                    return Err(PathError::BrokenLoop(cursor.position()))
+                }
+                // one end is outside the map
+                (Some(_), None) => return Err(PathError::DeadEnd(cursor.position())),
+                (Some(first), Some(second)) => {
+                    if first == previous {
+                        second
+                    } else {
+                        first
+                    }
+                }
+            };
+            path.push(cursor.position());
+            previous = cursor;
+            cursor = next;
         }
-        loop {
-            let neighbors = map.get_next_sectors(cursor);
-            match (path.contains(neighbors[0]), path.contains(neighbors[1])) {
-                (false, true) => cursor = neighbors[0],
-                (true, false) => cursor = neighbors[1],
-                (true, true) => break,
-                (false, false) => panic!(),
-            }
-            path.insert(*cursor);
+        Ok(path)
+    }
+}
+
+// The maze with the loop highlighted, without colors.
+impl Display for Path<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for line in Renderer::default().lines(self) {
+            writeln!(f, "{}", line)?;
         }
-        path
+        Ok(())
     }
 }
 
@@ -113,7 +257,7 @@ mod tests {
     fn create_loop() {
         let data = ".....\n.S-7.\n.|.|.\n.L-J.\n.....";
         let map: Map = data.parse().unwrap();
-        let path = Path::new(&map);
+        let path = Path::new(&map).unwrap();
         assert_eq!(path.steps_to_be_farthest(), 4);
     }
 
@@ -121,7 +265,7 @@ mod tests {
     fn get_inside_sectors_example_1() {
         let data = include_str!("resources/test_p2_1.txt");
         let map: Map = data.parse().unwrap();
-        let path = Path::new(&map);
+        let path = Path::new(&map).unwrap();
         let inside_sectors = path.get_sectors_inside_paths();
         assert_eq!(
             inside_sectors.len(),
@@ -135,7 +279,7 @@ mod tests {
     fn get_inside_sectors_example_2() {
         let data = include_str!("resources/test_p2_2.txt");
         let map: Map = data.parse().unwrap();
-        let path = Path::new(&map);
+        let path = Path::new(&map).unwrap();
         let inside_sectors = path.get_sectors_inside_paths();
         assert_eq!(
             inside_sectors.len(),
@@ -149,7 +293,7 @@ mod tests {
     fn get_inside_sectors_example_3() {
         let data = include_str!("resources/test_p2_3.txt");
         let map: Map = data.parse().unwrap();
-        let path = Path::new(&map);
+        let path = Path::new(&map).unwrap();
         let inside_sectors = path.get_sectors_inside_paths();
         assert_eq!(
             inside_sectors.len(),
@@ -163,7 +307,7 @@ mod tests {
     fn get_inside_sectors_example_4() {
         let data = include_str!("resources/test_p2_4.txt");
         let map: Map = data.parse().unwrap();
-        let path = Path::new(&map);
+        let path = Path::new(&map).unwrap();
         let inside_sectors = path.get_sectors_inside_paths();
         assert_eq!(
             inside_sectors.len(),
@@ -172,4 +316,123 @@ mod tests {
             inside_sectors
         );
     }
+
+    #[test]
+    fn dead_end() {
+        let data = ".....\n.S-7.\n.|.|.\n.L-..\n.....";
+        let map: Map = data.parse().unwrap();
+        assert_eq!(
+            Path::new(&map).err(),
+            Some(PathError::DeadEnd(Position(2, 1)))
+        );
+    }
+
+    #[test]
+    fn dead_end_out_of_map() {
+        let data = "S-7\n|.|\nL-7";
+        let map: Map = data.parse().unwrap();
+        assert_eq!(
+            Path::new(&map).err(),
+            Some(PathError::DeadEnd(Position(2, 0)))
+        );
+    }
+
+    #[test]
+    fn dead_end_at_start() {
+        let map: Map = "S-.".parse().unwrap();
+        assert_eq!(
+            Path::new(&map).err(),
+            Some(PathError::DeadEnd(Position(0, 0)))
+        );
+    }
+
+    #[test]
+    fn ambiguous_start() {
+        let map: Map = ".|.\n-S-\n.|.".parse().unwrap();
+        assert_eq!(Path::new(&map).err(), Some(PathError::AmbiguousStart(4)));
+    }
+
+    #[test]
+    fn broken_loop() {
+        let data = ".....\n.S-7.\n.|.|.\n.L|J.\n.....";
+        let map: Map = data.parse().unwrap();
+        assert_eq!(
+            Path::new(&map).err(),
+            Some(PathError::BrokenLoop(Position(2, 1)))
+        );
+    }
+
+    #[test]
+    fn loop_order() {
+        let data = ".....\n.S-7.\n.|.|.\n.L-J.\n.....";
+        let map: Map = data.parse().unwrap();
+        let path = Path::new(&map).unwrap();
+        assert_eq!(path.loop_length(), 8);
+        let forward: Vec<Position> = path.iter().copied().collect();
+        assert_eq!(forward[0], Position(1, 3));
+        assert_eq!(forward[1], Position(1, 2));
+        let backward: Vec<Position> = path.iter_rev().copied().collect();
+        assert_eq!(backward[0], Position(1, 3));
+        assert_eq!(backward[1], Position(2, 3));
+        assert_eq!(backward[7], Position(1, 2));
+    }
+
+    #[test]
+    fn steps_from_start() {
+        let data = ".....\n.S-7.\n.|.|.\n.L-J.\n.....";
+        let map: Map = data.parse().unwrap();
+        let path = Path::new(&map).unwrap();
+        assert_eq!(path.steps_from_start(&Position(1, 3)), Some((0, 0)));
+        assert_eq!(path.steps_from_start(&Position(1, 1)), Some((2, 6)));
+        assert_eq!(path.distance_from_start(&Position(3, 2)), Some(3));
+        assert_eq!(path.steps_from_start(&Position(2, 2)), None);
+        assert!(!path.contains(&Position(0, 0)));
+    }
+
+    #[test]
+    fn farthest_sector() {
+        let data = ".....\n.S-7.\n.|.|.\n.L-J.\n.....";
+        let map: Map = data.parse().unwrap();
+        let path = Path::new(&map).unwrap();
+        assert_eq!(path.farthest_sector().position(), Position(3, 1));
+        assert_eq!(path.farthest_sector().pipe_type(), PipeType::NorthWest);
+    }
+
+    #[test]
+    fn shoelace_matches_ray_casting() {
+        let fixtures = [
+            ".....\n.S-7.\n.|.|.\n.L-J.\n.....",
+            include_str!("resources/test_p2_1.txt"),
+            include_str!("resources/test_p2_2.txt"),
+            include_str!("resources/test_p2_3.txt"),
+            include_str!("resources/test_p2_4.txt"),
+            include_str!("resources/input.txt"),
+        ];
+        for data in fixtures {
+            let map: Map = data.parse().unwrap();
+            let path = Path::new(&map).unwrap();
+            assert_eq!(
+                path.count_sectors_inside_path(),
+                path.get_sectors_inside_paths().len(),
+                "Shoelace and ray casting disagree on\n{}",
+                data
+            );
+        }
+    }
+
+    #[test]
+    fn shoelace_example_4() {
+        let data = include_str!("resources/test_p2_4.txt");
+        let map: Map = data.parse().unwrap();
+        let path = Path::new(&map).unwrap();
+        assert_eq!(path.count_sectors_inside_path(), 10);
+    }
+
+    #[test]
+    fn display_path() {
+        let data = ".....\n.S-7.\n.|.|.\n.L-J.\n.....";
+        let map: Map = data.parse().unwrap();
+        let path = Path::new(&map).unwrap();
+        assert_eq!(path.to_string(), "OOOOO\nOS━┓O\nO┃I┃O\nO┗━*O\nOOOOO\n");
+    }
 }
@@ -1,4 +1,5 @@
-use super::position::Displacement;
+use super::alphabet::TileAlphabet;
+use super::direction::Direction;
 use std::error::Error;
 use std::fmt::Display;
 
@@ -14,53 +15,126 @@ pub enum PipeType {
     StartingPosition,
 }
 
-// TODO should rename that error, this is about pipe type
+// A character that isn't part of the tile alphabet. The grid parser adds where it was found
+// with at.
 #[derive(Debug, Eq, PartialEq)]
-pub struct InvalidPositionError(pub char);
+pub struct UnknownTileError(pub char);
 
-impl Display for InvalidPositionError {
+impl UnknownTileError {
+    pub fn at(self, row: usize, column: usize) -> PipeTypeError {
+        PipeTypeError {
+            symbol: self.0,
+            row,
+            column,
+        }
+    }
+}
+
+impl Display for UnknownTileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid pipe type. Symbol found: {}", self.0)
+    }
+}
+
+impl Error for UnknownTileError {}
+
+// A character that isn't part of the tile alphabet, with the row and column it was found at.
+#[derive(Debug, Eq, PartialEq)]
+pub struct PipeTypeError {
+    pub symbol: char,
+    pub row: usize,
+    pub column: usize,
+}
+
+impl Display for PipeTypeError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "Invalid pipe type during conversion. Symbol found: {}",
-            self.0
+            "Invalid pipe type at row {} column {}. Symbol found: {}",
+            self.row, self.column, self.symbol
         )
     }
 }
 
-impl Error for InvalidPositionError {}
+impl Error for PipeTypeError {}
 
+impl Display for PipeType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_box_drawing(false))
+    }
+}
+
+// A lone character, read with the classic alphabet of the puzzle input.
 impl TryFrom<char> for PipeType {
-    type Error = InvalidPositionError;
+    type Error = UnknownTileError;
 
     fn try_from(value: char) -> Result<Self, Self::Error> {
-        match value {
-            '|' => Ok(PipeType::NorthSouth),
-            '-' => Ok(PipeType::EastWest),
-            'L' => Ok(PipeType::NorthEast),
-            'J' => Ok(PipeType::NorthWest),
-            '7' => Ok(PipeType::SouthWest),
-            'F' => Ok(PipeType::SouthEast),
-            '.' => Ok(PipeType::Ground),
-            'S' => Ok(PipeType::StartingPosition),
-            invalid => Err(InvalidPositionError(invalid)),
-        }
+        TileAlphabet::classic_tile(value).ok_or(UnknownTileError(value))
     }
 }
 
 impl PipeType {
-    pub fn get_valid_connections(&self) -> Vec<Displacement> {
+    // Every actual pipe, without the ground and the starting position
+    pub const PIPES: [PipeType; 6] = [
+        PipeType::NorthSouth,
+        PipeType::EastWest,
+        PipeType::NorthEast,
+        PipeType::NorthWest,
+        PipeType::SouthWest,
+        PipeType::SouthEast,
+    ];
+
+    // Box-drawing character for the pipe, the heavy variant is used to highlight the loop.
+    pub fn to_box_drawing(&self, heavy: bool) -> char {
+        match (self, heavy) {
+            (PipeType::NorthSouth, false) => '│',
+            (PipeType::EastWest, false) => '─',
+            (PipeType::NorthEast, false) => '└',
+            (PipeType::NorthWest, false) => '┘',
+            (PipeType::SouthWest, false) => '┐',
+            (PipeType::SouthEast, false) => '┌',
+            (PipeType::NorthSouth, true) => '┃',
+            (PipeType::EastWest, true) => '━',
+            (PipeType::NorthEast, true) => '┗',
+            (PipeType::NorthWest, true) => '┛',
+            (PipeType::SouthWest, true) => '┓',
+            (PipeType::SouthEast, true) => '┏',
+            (PipeType::Ground, _) => ' ',
+            (PipeType::StartingPosition, _) => 'S',
+        }
+    }
+
+    pub fn connections(&self) -> &'static [Direction] {
         match self {
-            PipeType::NorthSouth => vec![Displacement(0, -1), Displacement(0, 1)],
-            PipeType::EastWest => vec![Displacement(-1, 0), Displacement(1, 0)],
-            PipeType::NorthEast => vec![Displacement(0, 1), Displacement(1, 0)],
-            PipeType::NorthWest => vec![Displacement(0, 1), Displacement(-1, 0)],
-            PipeType::SouthWest => vec![Displacement(0, -1), Displacement(-1, 0)],
-            PipeType::SouthEast => vec![Displacement(0, -1), Displacement(1, 0)],
-            PipeType::Ground => vec![],
-            PipeType::StartingPosition => vec![],
+            PipeType::NorthSouth => &[Direction::North, Direction::South],
+            PipeType::EastWest => &[Direction::East, Direction::West],
+            PipeType::NorthEast => &[Direction::North, Direction::East],
+            PipeType::NorthWest => &[Direction::North, Direction::West],
+            PipeType::SouthWest => &[Direction::South, Direction::West],
+            PipeType::SouthEast => &[Direction::South, Direction::East],
+            PipeType::Ground => &[],
+            PipeType::StartingPosition => &[],
+        }
+    }
+
+    // The two ends of the pipe, None for the ground and the unknown starting position.
+    pub fn directions(&self) -> Option<(Direction, Direction)> {
+        match self.connections() {
+            [first, second] => Some((*first, *second)),
+            _ => None,
         }
     }
+
+    pub fn connects(&self, direction: Direction) -> bool {
+        self.connections().contains(&direction)
+    }
+
+    // The pipe joining both directions in any order, None when they are the same.
+    pub fn from_directions(first: Direction, second: Direction) -> Option<PipeType> {
+        Self::PIPES.into_iter().find(|pipe_type| {
+            first != second && pipe_type.connects(first) && pipe_type.connects(second)
+        })
+    }
 }
 
 #[cfg(test)]
@@ -76,6 +150,43 @@ mod tests {
     #[test]
     fn position_from_invalid() {
         let pos = PipeType::try_from('X');
-        assert_eq!(pos, Err(InvalidPositionError('X')));
+        assert_eq!(pos, Err(UnknownTileError('X')));
+    }
+
+    #[test]
+    fn display_box_drawing() {
+        assert_eq!(PipeType::SouthEast.to_string(), "┌");
+        assert_eq!(PipeType::NorthWest.to_box_drawing(true), '┛');
+    }
+
+    #[test]
+    fn pipe_from_directions() {
+        assert_eq!(
+            PipeType::from_directions(Direction::West, Direction::South),
+            Some(PipeType::SouthWest)
+        );
+        assert_eq!(
+            PipeType::from_directions(Direction::North, Direction::North),
+            None
+        );
+        for direction in Direction::ALL {
+            for other in Direction::ALL {
+                if let Some(pipe_type) = PipeType::from_directions(direction, other) {
+                    assert!(pipe_type.connects(direction) && pipe_type.connects(other));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn pipe_connections() {
+        assert!(PipeType::NorthEast.connects(Direction::East));
+        assert!(!PipeType::NorthEast.connects(Direction::South));
+        assert!(!PipeType::Ground.connects(Direction::North));
+        assert_eq!(
+            PipeType::EastWest.directions(),
+            Some((Direction::East, Direction::West))
+        );
+        assert_eq!(PipeType::StartingPosition.directions(), None);
     }
 }
@@ -0,0 +1,121 @@
+use crate::day10_pipe_maze::path::Path;
+use crate::day10_pipe_maze::position::Position;
+use std::collections::HashSet;
+use std::io;
+
+const RESET: &str = "\x1b[0m";
+const LOOP_COLOR: &str = "\x1b[1;32m";
+const START_COLOR: &str = "\x1b[1;35m";
+const FARTHEST_COLOR: &str = "\x1b[1;31m";
+const INSIDE_COLOR: &str = "\x1b[33m";
+const OUTSIDE_COLOR: &str = "\x1b[2;34m";
+
+// Draws the maze with the loop in heavy box-drawing characters and every other sector
+// marked as inside or outside of it.
+#[derive(Debug, Clone)]
+pub struct Renderer {
+    pub color: bool,
+    pub inside_symbol: char,
+    pub outside_symbol: char,
+    pub farthest_symbol: char,
+}
+
+impl Default for Renderer {
+    fn default() -> Self {
+        Self {
+            color: false,
+            inside_symbol: 'I',
+            outside_symbol: 'O',
+            farthest_symbol: '*',
+        }
+    }
+}
+
+impl Renderer {
+    pub fn render(&self, path: &Path, out: &mut impl io::Write) -> io::Result<()> {
+        for line in self.lines(path) {
+            writeln!(out, "{}", line)?;
+        }
+        Ok(())
+    }
+
+    // One string per row of the map, north first.
+    pub fn lines(&self, path: &Path) -> Vec<String> {
+        let map = path.map();
+        let inside: HashSet<Position> = path
+            .get_sectors_inside_paths()
+            .iter()
+            .map(|sector| sector.position())
+            .collect();
+        let starting_position = map.get_starting_point().position();
+        let farthest_position = path.farthest_sector().position();
+        (0..map.get_height() as isize)
+            .rev()
+            .map(|y| {
+                let mut line = String::new();
+                for x in 0..map.get_width() as isize {
+                    let position = Position(x, y);
+                    let (symbol, color) = if position == starting_position {
+                        ('S', START_COLOR)
+                    } else if position == farthest_position {
+                        (self.farthest_symbol, FARTHEST_COLOR)
+                    } else if path.contains(&position) {
+                        let pipe_type = map.get_sector(position).unwrap().pipe_type();
+                        (pipe_type.to_box_drawing(true), LOOP_COLOR)
+                    } else if inside.contains(&position) {
+                        (self.inside_symbol, INSIDE_COLOR)
+                    } else {
+                        (self.outside_symbol, OUTSIDE_COLOR)
+                    };
+                    if self.color {
+                        line.push_str(color);
+                        line.push(symbol);
+                        line.push_str(RESET);
+                    } else {
+                        line.push(symbol);
+                    }
+                }
+                line
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day10_pipe_maze::map::Map;
+
+    #[test]
+    fn render_loop() {
+        let data = "-L|F7\n7S-7|\nL|7||\n-L-J|\nL|-JF";
+        let map: Map = data.parse().unwrap();
+        let path = Path::new(&map).unwrap();
+        let mut out: Vec<u8> = Vec::new();
+        Renderer::default().render(&path, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "OOOOO\nOS━┓O\nO┃I┃O\nO┗━*O\nOOOOO\n"
+        );
+    }
+
+    #[test]
+    fn render_with_color() {
+        let data = "S7\nLJ";
+        let map: Map = data.parse().unwrap();
+        let path = Path::new(&map).unwrap();
+        let renderer = Renderer {
+            color: true,
+            ..Default::default()
+        };
+        let lines = renderer.lines(&path);
+        assert_eq!(
+            lines[0],
+            format!("{}S{}{}┓{}", START_COLOR, RESET, LOOP_COLOR, RESET)
+        );
+        assert_eq!(
+            lines[1],
+            format!("{}┗{}{}*{}", LOOP_COLOR, RESET, FARTHEST_COLOR, RESET)
+        );
+    }
+}
@@ -1,9 +1,13 @@
+use crate::day10_pipe_maze::animation::{self, Player};
 use crate::day10_pipe_maze::map::Map;
+use std::fs;
+use std::io;
+use std::time::Duration;
 
 pub fn part_1() -> Result<String, String> {
     let data = include_str!("resources/input.txt");
-    let map: Map = data.parse().unwrap();
-    let path = crate::day10_pipe_maze::path::Path::new(&map);
+    let map: Map = data.parse().map_err(|err| format!("{}", err))?;
+    let path = crate::day10_pipe_maze::path::Path::new(&map).map_err(|err| format!("{}", err))?;
     Ok(format!(
         "The number of steps needed to be the furthest: {}",
         path.steps_to_be_farthest()
@@ -12,11 +16,56 @@ pub fn part_1() -> Result<String, String> {
 
 pub fn part_2() -> Result<String, String> {
     let data = include_str!("resources/input.txt");
-    let map: Map = data.parse().unwrap();
-    let path = crate::day10_pipe_maze::path::Path::new(&map);
+    let map: Map = data.parse().map_err(|err| format!("{}", err))?;
+    let path = crate::day10_pipe_maze::path::Path::new(&map).map_err(|err| format!("{}", err))?;
     let nb_inside_sectors = path.get_sectors_inside_paths().len();
     Ok(format!(
         "The number of inside sectors is: {}",
         nb_inside_sectors
     ))
 }
+
+// `loop|scan [--delay MS] [--every N] [--dump DIR] [--file MAP]`, the puzzle input is used
+// when no file is given.
+pub fn animate(args: &[String]) -> Result<(), String> {
+    let option = |name: &str| {
+        args.iter()
+            .position(|arg| arg == name)
+            .and_then(|index| args.get(index + 1))
+    };
+    let number = |name: &str| -> Result<Option<u64>, String> {
+        option(name)
+            .map(|value| {
+                value
+                    .parse::<u64>()
+                    .map_err(|err| format!("invalid {} {}: {}", name, value, err))
+            })
+            .transpose()
+    };
+    let mode = args.first().map(String::as_str);
+    let mut player = Player::default();
+    if let Some(delay) = number("--delay")? {
+        player.delay = Duration::from_millis(delay);
+    }
+    if let Some(every) = number("--every")? {
+        player.every = every as usize;
+    }
+    let data = match option("--file") {
+        Some(file) => fs::read_to_string(file).map_err(|err| format!("{}: {}", file, err))?,
+        None => include_str!("resources/input.txt").to_string(),
+    };
+    let map: Map = data.parse().map_err(|err| format!("{}", err))?;
+    let path = crate::day10_pipe_maze::path::Path::new(&map).map_err(|err| format!("{}", err))?;
+    let frames: Box<dyn Iterator<Item = animation::Frame>> = match mode {
+        Some("loop") => Box::new(animation::traversal_frames(&path)),
+        Some("scan") => Box::new(animation::scan_frames(&path)),
+        _ => return Err("expected loop or scan".to_string()),
+    };
+    let count = match option("--dump") {
+        Some(directory) => player.dump(frames, std::path::Path::new(directory)),
+        None => player.play(frames, &mut io::stdout()),
+    }
+    .map_err(|err| format!("{}", err))?;
+    eprintln!("{} frames", count);
+    Ok(())
+}
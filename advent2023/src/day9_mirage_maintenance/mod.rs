@@ -1,21 +1,25 @@
-mod sequence;
+pub mod polynomial;
+pub mod sequence;
 pub fn entrypoint() -> Result<String, String> {
     println!("Day 9 Maintenance maintenance");
     // The input
     let input = include_str!("resources/input.txt");
     // Parse to create the Sequences
-    let sequences: Vec<sequence::Sequence> = input
+    let sequences: Vec<sequence::Sequence<i64>> = input
         .lines()
         .map(str::parse)
         .collect::<Result<_, _>>()
-        .map_err(|err| format!("parse error {:?}", err).to_string())?;
+        .map_err(|err| format!("parse error {}", err))?;
     // Do the sum of the predictions
-    let prediction_sum: i32 = sequences.iter().map(sequence::Sequence::predict_next).sum();
+    let prediction_sum = checked_sum(sequences.iter().map(sequence::Sequence::predict_next))
+        .map_err(|err| format!("prediction error {}", err))?;
     // Do the sum of the extrapolations
-    let extrapolation_sum: i32 = sequences
-        .iter()
-        .map(sequence::Sequence::extrapolate_previous)
-        .sum();
+    let extrapolation_sum = checked_sum(
+        sequences
+            .iter()
+            .map(sequence::Sequence::extrapolate_previous),
+    )
+    .map_err(|err| format!("extrapolation error {}", err))?;
     Ok(
         format!(
             "The result from OASIS is {} for the sum of predictions and {} for the sum of extrapolations",
@@ -23,3 +27,12 @@ pub fn entrypoint() -> Result<String, String> {
         ).to_string()
     )
 }
+
+fn checked_sum(
+    mut values: impl Iterator<Item = Result<i64, sequence::SequenceError>>,
+) -> Result<i64, sequence::SequenceError> {
+    values.try_fold(0i64, |acc, value| {
+        acc.checked_add(value?)
+            .ok_or(sequence::SequenceError::Overflow)
+    })
+}
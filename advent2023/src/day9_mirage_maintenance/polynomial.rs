@@ -0,0 +1,169 @@
+use super::sequence::{DifferencePyramid, SequenceError, SequenceValue};
+use num::{BigInt, BigRational, CheckedDiv, CheckedMul, FromPrimitive, One, Zero};
+
+// The polynomial going through every value of a sequence, in Newton forward form:
+// P(x) = sum of newton_coefficients[j] * C(x, j), where x is the index in the sequence
+// (0 for the first reading) and C is the binomial coefficient.
+#[derive(Debug, PartialEq)]
+pub struct Polynomial<T> {
+    newton_coefficients: Vec<T>,
+}
+
+impl<T: SequenceValue> Polynomial<T> {
+    // The forward differences are the first value of every row of the pyramid. The pyramid
+    // must end with a non-empty all-zero row, otherwise the degree can't be known.
+    pub fn from_pyramid(pyramid: &DifferencePyramid<T>) -> Result<Self, SequenceError> {
+        let rows = pyramid.rows();
+        if rows.last().is_none_or(Vec::is_empty) {
+            return Err(SequenceError::NotPolynomial);
+        }
+        let newton_coefficients = rows[..rows.len() - 1]
+            .iter()
+            .map(|row| row[0].clone())
+            .collect();
+        Ok(Polynomial {
+            newton_coefficients,
+        })
+    }
+
+    pub fn degree(&self) -> usize {
+        self.newton_coefficients.len().saturating_sub(1)
+    }
+}
+
+impl<T> Polynomial<T>
+where
+    T: SequenceValue + One + CheckedMul + CheckedDiv + FromPrimitive,
+{
+    // Value at any index, before or after the data, in O(degree).
+    pub fn evaluate(&self, index: i64) -> Result<T, SequenceError> {
+        let mut result = T::zero();
+        // C(index, 0)
+        let mut binomial = T::one();
+        for (j, coefficient) in self.newton_coefficients.iter().enumerate() {
+            if j > 0 {
+                // C(x, j) = C(x, j - 1) * (x - j + 1) / j, the division is always exact
+                let factor = index
+                    .checked_sub(j as i64 - 1)
+                    .and_then(T::from_i64)
+                    .ok_or(SequenceError::Overflow)?;
+                let divisor = T::from_usize(j).ok_or(SequenceError::Overflow)?;
+                binomial = binomial
+                    .checked_mul(&factor)
+                    .and_then(|product| product.checked_div(&divisor))
+                    .ok_or(SequenceError::Overflow)?;
+            }
+            let term = coefficient
+                .checked_mul(&binomial)
+                .ok_or(SequenceError::Overflow)?;
+            result = result.checked_add(&term).ok_or(SequenceError::Overflow)?;
+        }
+        Ok(result)
+    }
+}
+
+impl<T> Polynomial<T>
+where
+    T: Clone,
+    BigInt: From<T>,
+{
+    // Coefficients in the usual power basis, constant term first.
+    pub fn coefficients(&self) -> Vec<BigRational> {
+        let mut coefficients: Vec<BigRational> =
+            vec![BigRational::zero(); self.newton_coefficients.len()];
+        // C(x, j) in the power basis, starting with C(x, 0) = 1
+        let mut binomial: Vec<BigRational> = vec![BigRational::one()];
+        for (j, coefficient) in self.newton_coefficients.iter().enumerate() {
+            if j > 0 {
+                // multiply by (x - j + 1) / j
+                let shift = index_to_rational(j - 1);
+                let divisor = index_to_rational(j);
+                let mut next = vec![BigRational::zero(); binomial.len() + 1];
+                for (power, value) in binomial.iter().enumerate() {
+                    next[power + 1] += value / &divisor;
+                    next[power] -= value * &shift / &divisor;
+                }
+                binomial = next;
+            }
+            let coefficient = BigRational::from_integer(BigInt::from(coefficient.clone()));
+            for (power, value) in binomial.iter().enumerate() {
+                coefficients[power] += &coefficient * value;
+            }
+        }
+        coefficients
+    }
+}
+
+fn index_to_rational(index: usize) -> BigRational {
+    BigRational::from_integer(BigInt::from(index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::sequence::Sequence;
+    use super::*;
+
+    fn fit(data: &str) -> Polynomial<i64> {
+        data.parse::<Sequence<i64>>().unwrap().fit().unwrap()
+    }
+
+    #[test]
+    fn evaluate_within_data() {
+        let polynomial = fit("10 13 16 21 30 45");
+        let values: Vec<i64> = (0..6).map(|i| polynomial.evaluate(i).unwrap()).collect();
+        assert_eq!(values, vec![10, 13, 16, 21, 30, 45]);
+    }
+
+    #[test]
+    fn evaluate_far_future_and_past() {
+        let polynomial = fit("1 3 6 10 15 21");
+        // triangular numbers: P(x) = (x + 1)(x + 2) / 2
+        assert_eq!(polynomial.evaluate(6), Ok(28));
+        assert_eq!(polynomial.evaluate(999), Ok(1000 * 1001 / 2));
+        assert_eq!(polynomial.evaluate(-1), Ok(0));
+        assert_eq!(polynomial.evaluate(-10), Ok(36));
+    }
+
+    #[test]
+    fn evaluate_matches_extrapolation() {
+        let sequence: Sequence<i64> = "10 13 16 21 30 45".parse().unwrap();
+        let polynomial = sequence.fit().unwrap();
+        let previous: Vec<i64> = (1..=3).map(|i| polynomial.evaluate(-i).unwrap()).collect();
+        assert_eq!(previous, sequence.extrapolate(3).unwrap());
+    }
+
+    #[test]
+    fn evaluate_overflow() {
+        let polynomial = fit("0 1 4 9 16");
+        assert_eq!(polynomial.evaluate(i64::MAX), Err(SequenceError::Overflow));
+    }
+
+    #[test]
+    fn degree_and_coefficients() {
+        let polynomial = fit("1 3 6 10 15 21");
+        assert_eq!(polynomial.degree(), 2);
+        let half = BigRational::new(BigInt::from(1), BigInt::from(2));
+        assert_eq!(
+            polynomial.coefficients(),
+            vec![
+                BigRational::from_integer(BigInt::from(1)),
+                &half * BigInt::from(3),
+                half
+            ]
+        );
+    }
+
+    #[test]
+    fn zero_sequence() {
+        let polynomial = fit("0 0 0");
+        assert_eq!(polynomial.degree(), 0);
+        assert_eq!(polynomial.evaluate(42), Ok(0));
+        assert!(polynomial.coefficients().is_empty());
+    }
+
+    #[test]
+    fn not_polynomial_within_length() {
+        let sequence: Sequence<i64> = "1 2 4 8 16".parse().unwrap();
+        assert_eq!(sequence.fit(), Err(SequenceError::NotPolynomial));
+    }
+}
@@ -1,18 +1,50 @@
-use std::num::ParseIntError;
+use super::polynomial::Polynomial;
+use num::{CheckedAdd, CheckedSub, Zero};
+use std::fmt::Display;
 use std::str::FromStr;
 
+// Anything that can hold a reading: fixed-width integers (i64, i128...) or num::BigInt.
+// Arithmetic is always checked so an overflow is reported instead of wrapping.
+pub trait SequenceValue: Clone + PartialEq + Zero + CheckedAdd + CheckedSub + FromStr {}
+
+impl<T> SequenceValue for T where T: Clone + PartialEq + Zero + CheckedAdd + CheckedSub + FromStr {}
+
 #[derive(Debug, PartialEq)]
-pub struct Sequence {
-    data: Vec<i32>,
+pub struct Sequence<T = i64> {
+    data: Vec<T>,
 }
 
 #[derive(Debug, PartialEq)]
 pub enum SequenceError {
     EmptyInput,
-    ParseError(ParseIntError),
+    ParseError(String),
+    Overflow,
+    NotPolynomial,
 }
 
-impl FromStr for Sequence {
+impl Display for SequenceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SequenceError::EmptyInput => write!(f, "The source string is empty. Nothing to parse."),
+            SequenceError::ParseError(err) => write!(f, "Invalid value in sequence: {}", err),
+            SequenceError::Overflow => {
+                write!(f, "Arithmetic overflow while extrapolating the sequence.")
+            }
+            SequenceError::NotPolynomial => write!(
+                f,
+                "The differences never reach an all-zero row, the sequence is too short to be fitted."
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SequenceError {}
+
+impl<T> FromStr for Sequence<T>
+where
+    T: SequenceValue,
+    <T as FromStr>::Err: Display,
+{
     type Err = SequenceError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -21,67 +53,164 @@ impl FromStr for Sequence {
             return Err(SequenceError::EmptyInput);
         }
 
-        let data: Result<Vec<i32>, ParseIntError> = s.split_whitespace().map(str::parse).collect();
+        let data: Result<Vec<T>, _> = s.split_whitespace().map(str::parse).collect();
 
         match data {
             Ok(vec) => Ok(Sequence { data: vec }),
-            Err(e) => Err(SequenceError::ParseError(e)),
+            Err(e) => Err(SequenceError::ParseError(e.to_string())),
         }
     }
 }
 
-impl Sequence {
-    fn generate_subsequences(&self) -> Vec<Vec<i32>> {
-        let mut subsequences: Vec<Vec<i32>> = vec![self.data.clone()];
-        while !subsequences.last().unwrap().iter().all(|v| *v == 0) {
+// The successive differences of a sequence, down to the first all-zero row.
+#[derive(Debug, PartialEq)]
+pub struct DifferencePyramid<T> {
+    rows: Vec<Vec<T>>,
+}
+
+impl<T: SequenceValue> DifferencePyramid<T> {
+    pub fn rows(&self) -> &[Vec<T>] {
+        &self.rows
+    }
+
+    // Degree of the polynomial generating the sequence: the number of difference rows
+    // before the constant one. An all-zero sequence is reported as degree 0.
+    pub fn degree(&self) -> Result<usize, SequenceError> {
+        Ok(self.non_zero_rows()?.count().saturating_sub(1))
+    }
+
+    // The next k values after the sequence, in order.
+    pub fn predict(&self, k: usize) -> Result<Vec<T>, SequenceError> {
+        let mut edges: Vec<T> = self
+            .non_zero_rows()?
+            .map(|row| row.last().unwrap().clone())
+            .collect();
+        (0..k)
+            .map(|_| {
+                for index in (0..edges.len().saturating_sub(1)).rev() {
+                    edges[index] = edges[index]
+                        .checked_add(&edges[index + 1])
+                        .ok_or(SequenceError::Overflow)?;
+                }
+                Ok(edges.first().cloned().unwrap_or_else(T::zero))
+            })
+            .collect()
+    }
+
+    // The k values before the sequence, closest first.
+    pub fn extrapolate(&self, k: usize) -> Result<Vec<T>, SequenceError> {
+        let mut edges: Vec<T> = self
+            .non_zero_rows()?
+            .map(|row| row.first().unwrap().clone())
+            .collect();
+        (0..k)
+            .map(|_| {
+                for index in (0..edges.len().saturating_sub(1)).rev() {
+                    edges[index] = edges[index]
+                        .checked_sub(&edges[index + 1])
+                        .ok_or(SequenceError::Overflow)?;
+                }
+                Ok(edges.first().cloned().unwrap_or_else(T::zero))
+            })
+            .collect()
+    }
+
+    // Every row but the final all-zero one, none of them empty. When the differences run
+    // out before reaching a zero row the sequence isn't generated by a polynomial.
+    fn non_zero_rows(&self) -> Result<impl Iterator<Item = &Vec<T>>, SequenceError> {
+        if self.rows.last().is_none_or(Vec::is_empty) {
+            return Err(SequenceError::NotPolynomial);
+        }
+        Ok(self.rows.iter().take(self.rows.len() - 1))
+    }
+}
+
+impl<T: Display> Display for DifferencePyramid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let width = self
+            .rows
+            .iter()
+            .flatten()
+            .map(|value| value.to_string().len())
+            .max()
+            .unwrap_or(0);
+        for (depth, row) in self.rows.iter().enumerate() {
+            let values: Vec<String> = row
+                .iter()
+                .map(|value| format!("{:>width$}", value))
+                .collect();
+            let indent = depth * (width + 1) / 2;
+            writeln!(f, "{:indent$}{}", "", values.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: SequenceValue> Sequence<T> {
+    pub fn difference_pyramid(&self) -> Result<DifferencePyramid<T>, SequenceError> {
+        let mut subsequences: Vec<Vec<T>> = vec![self.data.clone()];
+        while !subsequences.last().unwrap().iter().all(T::is_zero) {
             let previous_subsequence = subsequences.last().unwrap();
             let subsequence = (1..previous_subsequence.len())
-                .map(|index| previous_subsequence[index] - previous_subsequence[index - 1])
-                .collect();
+                .map(|index| {
+                    previous_subsequence[index]
+                        .checked_sub(&previous_subsequence[index - 1])
+                        .ok_or(SequenceError::Overflow)
+                })
+                .collect::<Result<_, _>>()?;
             subsequences.push(subsequence);
         }
-        subsequences
+        Ok(DifferencePyramid { rows: subsequences })
     }
-    pub fn predict_next(&self) -> i32 {
-        let subsequences: Vec<Vec<i32>> = self.generate_subsequences();
-        let mut to_add = 0i32;
-        for sequence in subsequences.iter().rev().skip(1) {
-            to_add += sequence.last().unwrap();
-        }
-        to_add
+
+    pub fn degree(&self) -> Result<usize, SequenceError> {
+        self.difference_pyramid()?.degree()
     }
 
-    pub fn extrapolate_previous(&self) -> i32 {
-        let subsequences: Vec<Vec<i32>> = self.generate_subsequences();
-        let mut to_sub = 0i32;
-        for sequence in subsequences.iter().rev().skip(1) {
-            to_sub = sequence.first().unwrap() - to_sub;
-        }
-        to_sub
+    // Compute the generating polynomial once so any index can then be evaluated cheaply.
+    pub fn fit(&self) -> Result<Polynomial<T>, SequenceError> {
+        Polynomial::from_pyramid(&self.difference_pyramid()?)
+    }
+
+    pub fn predict(&self, k: usize) -> Result<Vec<T>, SequenceError> {
+        self.difference_pyramid()?.predict(k)
+    }
+
+    pub fn extrapolate(&self, k: usize) -> Result<Vec<T>, SequenceError> {
+        self.difference_pyramid()?.extrapolate(k)
+    }
+
+    pub fn predict_next(&self) -> Result<T, SequenceError> {
+        Ok(self.predict(1)?.remove(0))
+    }
+
+    pub fn extrapolate_previous(&self) -> Result<T, SequenceError> {
+        Ok(self.extrapolate(1)?.remove(0))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use num::BigInt;
 
     #[test]
     fn create_sequence_from_string() {
         let data = "12 42 -7 5 0";
         let expected_data = vec![12, 42, -7, 5, 0];
-        assert_eq!(Sequence::from_str(data).unwrap().data, expected_data);
+        assert_eq!(Sequence::<i64>::from_str(data).unwrap().data, expected_data);
     }
 
     #[test]
     fn create_sequence_from_empty_string() {
-        let result = Sequence::from_str("");
+        let result = Sequence::<i64>::from_str("");
         assert!(result.is_err());
         assert_eq!(result, Err(SequenceError::EmptyInput));
     }
 
     #[test]
     fn create_sequence_from_garbage_string() {
-        let result = Sequence::from_str("10 arthur -5");
+        let result = Sequence::<i64>::from_str("10 arthur -5");
         assert!(result.is_err());
         match result {
             Err(SequenceError::ParseError(_)) => {}
@@ -91,13 +220,99 @@ mod tests {
 
     #[test]
     fn test_predict_next() {
-        let sequence = Sequence::from_str("1 3 6 10 15 21").unwrap();
-        assert_eq!(sequence.predict_next(), 28)
+        let sequence = Sequence::<i64>::from_str("1 3 6 10 15 21").unwrap();
+        assert_eq!(sequence.predict_next(), Ok(28))
     }
 
     #[test]
     fn test_extrapolate_previous() {
-        let sequence = Sequence::from_str("10 13 16 21 30 45").unwrap();
-        assert_eq!(sequence.extrapolate_previous(), 5)
+        let sequence = Sequence::<i64>::from_str("10 13 16 21 30 45").unwrap();
+        assert_eq!(sequence.extrapolate_previous(), Ok(5))
+    }
+
+    #[test]
+    fn test_predict_next_overflow() {
+        let sequence = Sequence::<i64> {
+            data: vec![0, i64::MAX / 2, i64::MAX - 1],
+        };
+        assert_eq!(sequence.predict_next(), Err(SequenceError::Overflow));
+    }
+
+    #[test]
+    fn test_predict_next_i128() {
+        let sequence = Sequence::<i128> {
+            data: vec![0, i64::MAX as i128 / 2, i64::MAX as i128 - 1],
+        };
+        assert_eq!(sequence.predict_next(), Ok(3 * (i64::MAX as i128 / 2)));
+    }
+
+    #[test]
+    fn test_predict_next_big_int() {
+        let sequence = Sequence::<BigInt>::from_str(
+            "0 170141183460469231731687303715884105727 340282366920938463463374607431768211454",
+        )
+        .unwrap();
+        assert_eq!(
+            sequence.predict_next(),
+            Ok("510423550381407695195061911147652317181".parse().unwrap())
+        );
+        assert_eq!(
+            sequence.extrapolate_previous(),
+            Ok("-170141183460469231731687303715884105727".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_predict_k() {
+        let sequence = Sequence::<i64>::from_str("1 3 6 10 15 21").unwrap();
+        assert_eq!(sequence.predict(3), Ok(vec![28, 36, 45]));
+        assert_eq!(sequence.predict(0), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_extrapolate_k() {
+        let sequence = Sequence::<i64>::from_str("10 13 16 21 30 45").unwrap();
+        assert_eq!(sequence.extrapolate(3), Ok(vec![5, -4, -19]));
+    }
+
+    #[test]
+    fn test_predict_constant() {
+        let sequence = Sequence::<i64>::from_str("0 0 0").unwrap();
+        assert_eq!(sequence.predict(2), Ok(vec![0, 0]));
+        let sequence = Sequence::<i64>::from_str("7 7").unwrap();
+        assert_eq!(sequence.extrapolate(2), Ok(vec![7, 7]));
+        // A single reading doesn't tell whether it's constant
+        let sequence = Sequence::<i64>::from_str("7").unwrap();
+        assert_eq!(sequence.extrapolate(2), Err(SequenceError::NotPolynomial));
+    }
+
+    #[test]
+    fn test_degree() {
+        let sequence = Sequence::<i64>::from_str("0 3 6 9 12 15").unwrap();
+        assert_eq!(sequence.degree(), Ok(1));
+        let sequence = Sequence::<i64>::from_str("10 13 16 21 30 45").unwrap();
+        assert_eq!(sequence.degree(), Ok(3));
+    }
+
+    #[test]
+    fn test_not_polynomial() {
+        let sequence = Sequence::<i64>::from_str("1 2 4 8 16").unwrap();
+        assert_eq!(sequence.degree(), Err(SequenceError::NotPolynomial));
+        assert_eq!(sequence.predict(1), Err(SequenceError::NotPolynomial));
+        assert_eq!(sequence.extrapolate(1), Err(SequenceError::NotPolynomial));
+    }
+
+    #[test]
+    fn test_difference_pyramid() {
+        let sequence = Sequence::<i64>::from_str("1 3 6 10").unwrap();
+        let pyramid = sequence.difference_pyramid().unwrap();
+        assert_eq!(
+            pyramid.rows(),
+            &[vec![1, 3, 6, 10], vec![2, 3, 4], vec![1, 1], vec![0]]
+        );
+        assert_eq!(
+            pyramid.to_string(),
+            " 1  3  6 10\n  2  3  4\n    1  1\n     0\n"
+        );
     }
 }
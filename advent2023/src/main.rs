@@ -1,6 +1,15 @@
 use advent2023::day10_pipe_maze;
 use advent2023::day9_mirage_maintenance;
+use std::env;
 fn main() {
+    // `animate loop|scan [--delay MS] [--every N] [--dump DIR] [--file MAP]` for day 10
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("animate") {
+        if let Err(error) = day10_pipe_maze::animate(&args[1..]) {
+            println!("FAILURE: {}", error);
+        }
+        return;
+    }
     println!("Hello, world!");
     let entrypoints = vec![
         day9_mirage_maintenance::entrypoint,
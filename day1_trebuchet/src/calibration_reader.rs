@@ -0,0 +1,260 @@
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::io::BufRead;
+use std::io::BufReader;
+
+mod audit;
+mod matcher;
+mod vocabulary;
+
+pub use audit::{render_json, render_table, LineAudit};
+pub use matcher::Matcher;
+pub use vocabulary::Vocabulary;
+
+// Which numbers count: part 1 only reads digits, part 2 also English words.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CalibrationMode {
+    DigitsOnly,
+    English,
+    Custom(Vocabulary),
+}
+
+impl CalibrationMode {
+    pub fn vocabulary(&self) -> Vocabulary {
+        match self {
+            CalibrationMode::DigitsOnly => Vocabulary::default(),
+            CalibrationMode::English => Vocabulary::english(),
+            CalibrationMode::Custom(vocabulary) => vocabulary.clone(),
+        }
+    }
+}
+
+// A line without any number.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidLine {
+    // Starting at 1
+    pub line_number: usize,
+    pub content: String,
+}
+
+impl Display for InvalidLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {:?}", self.line_number, self.content)
+    }
+}
+
+#[derive(Debug)]
+pub enum CalibrationError {
+    Io(io::Error),
+    InvalidLine(InvalidLine),
+}
+
+impl Display for CalibrationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CalibrationError::Io(err) => write!(f, "Can't read the calibration document: {}", err),
+            CalibrationError::InvalidLine(line) => write!(f, "No digit found on {}", line),
+        }
+    }
+}
+
+impl std::error::Error for CalibrationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CalibrationError::Io(err) => Some(err),
+            CalibrationError::InvalidLine(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for CalibrationError {
+    fn from(err: io::Error) -> Self {
+        CalibrationError::Io(err)
+    }
+}
+
+// The calibration of the valid lines and the lines that were skipped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalibrationSummary {
+    pub total: u32,
+    pub valid_count: usize,
+    pub invalid_lines: Vec<InvalidLine>,
+}
+
+impl Display for CalibrationSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} from {} lines, {} skipped",
+            self.total,
+            self.valid_count,
+            self.invalid_lines.len()
+        )?;
+        for line in &self.invalid_lines {
+            write!(f, "\n  {}", line)?;
+        }
+        Ok(())
+    }
+}
+
+// Stops on the first line without a number.
+pub fn get_calibration(filename: &str, mode: &CalibrationMode) -> Result<u32, CalibrationError> {
+    let document_file = open_calibration_document(filename)?;
+    Ok(calibrate(document_file, mode, false)?.total)
+}
+
+// Leaves the lines without a number out of the total and lists them.
+pub fn get_calibration_skipping_invalid(
+    filename: &str,
+    mode: &CalibrationMode,
+) -> Result<CalibrationSummary, CalibrationError> {
+    let document_file = open_calibration_document(filename)?;
+    calibrate(document_file, mode, true)
+}
+
+// The tokens read on every line, whether they make a valid value or not.
+pub fn get_calibration_audit(
+    filename: &str,
+    mode: &CalibrationMode,
+) -> Result<Vec<LineAudit>, CalibrationError> {
+    let document_file = open_calibration_document(filename)?;
+    Ok(audit::audit(document_file, mode)?)
+}
+
+fn calibrate(
+    document: impl BufRead,
+    mode: &CalibrationMode,
+    skip_invalid: bool,
+) -> Result<CalibrationSummary, CalibrationError> {
+    let matcher = Matcher::new(&mode.vocabulary());
+    let mut summary = CalibrationSummary {
+        total: 0,
+        valid_count: 0,
+        invalid_lines: Vec::new(),
+    };
+    for (index, line) in get_line_iter(document).enumerate() {
+        let line = line?;
+        match extract_number_from_string(&line, &matcher) {
+            Some(number) => {
+                summary.total += number as u32;
+                summary.valid_count += 1;
+            }
+            None => {
+                let invalid_line = InvalidLine {
+                    line_number: index + 1,
+                    content: line,
+                };
+                if !skip_invalid {
+                    return Err(CalibrationError::InvalidLine(invalid_line));
+                }
+                summary.invalid_lines.push(invalid_line);
+            }
+        }
+    }
+    Ok(summary)
+}
+
+fn open_calibration_document(filename: &str) -> io::Result<BufReader<fs::File>> {
+    let file = fs::File::open(filename)?;
+    Ok(BufReader::new(file))
+}
+
+fn get_line_iter(buffer: impl BufRead) -> impl Iterator<Item = io::Result<String>> {
+    buffer.lines()
+}
+
+// The first and the last number of the line make a two-digit number, None when the line
+// doesn't have any.
+fn extract_number_from_string(string: &str, matcher: &Matcher) -> Option<u8> {
+    let first = matcher.first(string)?;
+    let last = matcher.last(string)?;
+    Some(first.value * 10 + last.value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calibration_values() {
+        let document = "two1nine\neightwothree\nabcone2threexyz\nxtwone3four\n\
+                        4nineeightseven2\nzoneight234\n7pqrstsixteen";
+        let matcher = Matcher::new(&Vocabulary::english());
+        let values: Vec<u8> = get_line_iter(document.as_bytes())
+            .map(|line| extract_number_from_string(&line.unwrap(), &matcher).unwrap())
+            .collect();
+        assert_eq!(values, vec![29, 83, 13, 24, 42, 14, 76]);
+        assert_eq!(values.iter().map(|value| *value as u32).sum::<u32>(), 281);
+    }
+
+    #[test]
+    fn calibration_modes() {
+        let document = "1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet\nfive6seven";
+        assert_eq!(
+            calibrate(document.as_bytes(), &CalibrationMode::DigitsOnly, false)
+                .unwrap()
+                .total,
+            142 + 66
+        );
+        assert_eq!(
+            calibrate(document.as_bytes(), &CalibrationMode::English, false)
+                .unwrap()
+                .total,
+            142 + 57
+        );
+        // The v of "vwx", "five" and "seven" count
+        let roman = Vocabulary::new([("v", 5), ("vi", 6), ("vii", 7)]);
+        assert_eq!(
+            calibrate(document.as_bytes(), &CalibrationMode::Custom(roman), false)
+                .unwrap()
+                .total,
+            12 + 35 + 15 + 77 + 55
+        );
+    }
+
+    #[test]
+    fn line_without_digit() {
+        let document = "1abc2\nabc\ntreb7uchet";
+        let result = calibrate(document.as_bytes(), &CalibrationMode::DigitsOnly, false);
+        match result {
+            Err(CalibrationError::InvalidLine(line)) => assert_eq!(
+                line,
+                InvalidLine {
+                    line_number: 2,
+                    content: "abc".to_string()
+                }
+            ),
+            _ => panic!("{:?}", result),
+        }
+        // "one" is a number in part 2
+        let result = calibrate("one\n".as_bytes(), &CalibrationMode::English, false);
+        assert_eq!(result.unwrap().total, 11);
+    }
+
+    #[test]
+    fn skip_invalid_lines() {
+        let document = "1abc2\nabc\ntreb7uchet\n\nfive";
+        let summary = calibrate(document.as_bytes(), &CalibrationMode::DigitsOnly, true).unwrap();
+        assert_eq!(summary.total, 12 + 77);
+        assert_eq!(summary.valid_count, 2);
+        assert_eq!(
+            summary
+                .invalid_lines
+                .iter()
+                .map(|line| line.line_number)
+                .collect::<Vec<usize>>(),
+            vec![2, 4, 5]
+        );
+        assert_eq!(
+            summary.to_string(),
+            "89 from 2 lines, 3 skipped\n  line 2: \"abc\"\n  line 4: \"\"\n  line 5: \"five\""
+        );
+    }
+
+    #[test]
+    fn missing_document() {
+        let result = get_calibration("does_not_exist.txt", &CalibrationMode::DigitsOnly);
+        assert!(matches!(result, Err(CalibrationError::Io(_))));
+    }
+}
@@ -0,0 +1,194 @@
+use super::matcher::{Matcher, Token, TokenKind};
+use super::CalibrationMode;
+use std::io::{self, BufRead};
+
+// What was read on one line of the calibration document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineAudit {
+    // Starting at 1
+    pub line_number: usize,
+    pub content: String,
+    pub first: Option<Token>,
+    pub last: Option<Token>,
+}
+
+impl LineAudit {
+    // The calibration value of the line, None when it doesn't have a number.
+    pub fn value(&self) -> Option<u8> {
+        Some(self.first?.value * 10 + self.last?.value)
+    }
+
+    fn text(&self, token: &Token) -> &str {
+        &self.content[token.start..token.end]
+    }
+}
+
+pub fn audit(document: impl BufRead, mode: &CalibrationMode) -> io::Result<Vec<LineAudit>> {
+    let matcher = Matcher::new(&mode.vocabulary());
+    document
+        .lines()
+        .enumerate()
+        .map(|(index, line)| {
+            let content = line?;
+            Ok(LineAudit {
+                line_number: index + 1,
+                first: matcher.first(&content),
+                last: matcher.last(&content),
+                content,
+            })
+        })
+        .collect()
+}
+
+fn kind_name(kind: TokenKind) -> &'static str {
+    match kind {
+        TokenKind::Digit => "digit",
+        TokenKind::Word => "word",
+    }
+}
+
+// One row per line, offsets as start..end in bytes.
+pub fn render_table(audits: &[LineAudit], out: &mut impl io::Write) -> io::Result<()> {
+    writeln!(
+        out,
+        "{:>5} | {:>5} | {:<22} | {:<22} | content",
+        "line", "value", "first", "last"
+    )?;
+    for line in audits {
+        let token_cell = |token: &Option<Token>| match token {
+            Some(token) => format!(
+                "{:<8} {:>3}..{:<3} {:<5}",
+                line.text(token),
+                token.start,
+                token.end,
+                kind_name(token.kind)
+            ),
+            None => "-".to_string(),
+        };
+        writeln!(
+            out,
+            "{:>5} | {:>5} | {:<22} | {:<22} | {}",
+            line.line_number,
+            line.value()
+                .map_or("-".to_string(), |value| value.to_string()),
+            token_cell(&line.first),
+            token_cell(&line.last),
+            line.content
+        )?;
+    }
+    Ok(())
+}
+
+fn json_string(s: &str) -> String {
+    let mut result = String::with_capacity(s.len() + 2);
+    result.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            c if (c as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+fn json_token(line: &LineAudit, token: &Option<Token>) -> String {
+    match token {
+        Some(token) => format!(
+            "{{\"text\": {}, \"value\": {}, \"start\": {}, \"end\": {}, \"kind\": \"{}\"}}",
+            json_string(line.text(token)),
+            token.value,
+            token.start,
+            token.end,
+            kind_name(token.kind)
+        ),
+        None => "null".to_string(),
+    }
+}
+
+// An array with one object per line, null for what wasn't found.
+pub fn render_json(audits: &[LineAudit], out: &mut impl io::Write) -> io::Result<()> {
+    writeln!(out, "[")?;
+    for (index, line) in audits.iter().enumerate() {
+        let separator = if index + 1 < audits.len() { "," } else { "" };
+        writeln!(
+            out,
+            "  {{\"line\": {}, \"content\": {}, \"value\": {}, \"first\": {}, \"last\": {}}}{}",
+            line.line_number,
+            json_string(&line.content),
+            line.value()
+                .map_or("null".to_string(), |value| value.to_string()),
+            json_token(line, &line.first),
+            json_token(line, &line.last),
+            separator
+        )?;
+    }
+    writeln!(out, "]")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn audits() -> Vec<LineAudit> {
+        let document = "two1nine\n\"oneight\"\nabc";
+        audit(document.as_bytes(), &CalibrationMode::English).unwrap()
+    }
+
+    #[test]
+    fn audit_lines() {
+        let audits = audits();
+        assert_eq!(audits.len(), 3);
+        assert_eq!(audits[0].value(), Some(29));
+        assert_eq!(
+            audits[0].first,
+            Some(Token {
+                value: 2,
+                start: 0,
+                end: 3,
+                kind: TokenKind::Word
+            })
+        );
+        assert_eq!(audits[1].value(), Some(18));
+        assert_eq!(audits[1].last.map(|token| token.start), Some(3));
+        assert_eq!(audits[2].value(), None);
+        assert_eq!(audits[2].line_number, 3);
+    }
+
+    #[test]
+    fn table() {
+        let mut out: Vec<u8> = Vec::new();
+        render_table(&audits(), &mut out).unwrap();
+        let table = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(
+            lines[1],
+            "    1 |    29 | two        0..3   word  | nine       4..8   word  | two1nine"
+        );
+        assert_eq!(
+            lines[3],
+            "    3 |     - | -                      | -                      | abc"
+        );
+    }
+
+    #[test]
+    fn json() {
+        let mut out: Vec<u8> = Vec::new();
+        render_json(&audits(), &mut out).unwrap();
+        let json = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = json.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(
+            lines[2],
+            "  {\"line\": 2, \"content\": \"\\\"oneight\\\"\", \"value\": 18, \
+             \"first\": {\"text\": \"one\", \"value\": 1, \"start\": 1, \"end\": 4, \"kind\": \"word\"}, \
+             \"last\": {\"text\": \"eight\", \"value\": 8, \"start\": 3, \"end\": 8, \"kind\": \"word\"}},"
+        );
+        assert_eq!(
+            lines[3],
+            "  {\"line\": 3, \"content\": \"abc\", \"value\": null, \"first\": null, \"last\": null}"
+        );
+    }
+}
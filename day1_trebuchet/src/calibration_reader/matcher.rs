@@ -0,0 +1,311 @@
+use super::vocabulary::Vocabulary;
+use std::collections::{HashMap, VecDeque};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Digit,
+    Word,
+}
+
+// A digit or a spelled-out number found in a line. Offsets are in bytes, end excluded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    pub value: u8,
+    pub start: usize,
+    pub end: usize,
+    pub kind: TokenKind,
+}
+
+// A match in chars, in the order the line is scanned.
+#[derive(Debug, Clone, Copy)]
+struct Match {
+    start: usize,
+    end: usize,
+    value: u8,
+    kind: TokenKind,
+}
+
+// Lowercase without the accent, one char for one so offsets stay the same.
+fn fold(c: char) -> char {
+    let lower = c.to_lowercase().next().unwrap_or(c);
+    match lower {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' => 'a',
+        'ç' => 'c',
+        'è' | 'é' | 'ê' | 'ë' => 'e',
+        'ì' | 'í' | 'î' | 'ï' => 'i',
+        'ñ' => 'n',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' => 'o',
+        'ù' | 'ú' | 'û' | 'ü' => 'u',
+        'ý' | 'ÿ' => 'y',
+        other => other,
+    }
+}
+
+#[derive(Debug, Default)]
+struct Node {
+    next: HashMap<char, usize>,
+    fail: usize,
+    // Length in chars and value of the words ending here, through the fail links too
+    outputs: Vec<(usize, u8)>,
+}
+
+// Aho-Corasick automaton: a trie of the words where each node also links to the longest
+// suffix of its prefix that is in the trie, so a text is matched against every word at
+// once in a single pass.
+#[derive(Debug)]
+struct Automaton {
+    nodes: Vec<Node>,
+}
+
+impl Automaton {
+    fn new<'a>(words: impl Iterator<Item = (&'a [char], u8)>) -> Self {
+        let mut nodes = vec![Node::default()];
+        for (word, value) in words {
+            let mut current = 0;
+            for c in word {
+                current = match nodes[current].next.get(c) {
+                    Some(next) => *next,
+                    None => {
+                        nodes.push(Node::default());
+                        let next = nodes.len() - 1;
+                        nodes[current].next.insert(*c, next);
+                        next
+                    }
+                };
+            }
+            nodes[current].outputs.push((word.len(), value));
+        }
+        let mut automaton = Automaton { nodes };
+        automaton.link();
+        automaton
+    }
+
+    // Breadth first, so the fail link of a node is ready before its children need it.
+    fn link(&mut self) {
+        let mut queue: VecDeque<usize> = self.nodes[0].next.values().copied().collect();
+        while let Some(current) = queue.pop_front() {
+            let children: Vec<(char, usize)> = self.nodes[current]
+                .next
+                .iter()
+                .map(|(c, child)| (*c, *child))
+                .collect();
+            for (c, child) in children {
+                let fail = if current == 0 {
+                    0
+                } else {
+                    self.step(self.nodes[current].fail, c)
+                };
+                self.nodes[child].fail = fail;
+                let inherited = self.nodes[fail].outputs.clone();
+                self.nodes[child].outputs.extend(inherited);
+                queue.push_back(child);
+            }
+        }
+    }
+
+    fn step(&self, mut state: usize, c: char) -> usize {
+        loop {
+            if let Some(next) = self.nodes[state].next.get(&c) {
+                return *next;
+            }
+            if state == 0 {
+                return 0;
+            }
+            state = self.nodes[state].fail;
+        }
+    }
+}
+
+// Finds the first and the last number of a line, digits or words of the vocabulary.
+// Overlapping words are all seen, so "eightwo" starts with 8 and ends with 2. Case and
+// accents are ignored, "FUNF" matches "fünf".
+#[derive(Debug)]
+pub struct Matcher {
+    forward: Automaton,
+    // Built on the reversed words to scan lines from the end
+    backward: Automaton,
+    longest: usize,
+}
+
+impl Matcher {
+    pub fn new(vocabulary: &Vocabulary) -> Self {
+        let words: Vec<(Vec<char>, u8)> = vocabulary
+            .words()
+            .iter()
+            .map(|(word, value)| (word.chars().map(fold).collect(), *value))
+            .collect();
+        let reversed: Vec<(Vec<char>, u8)> = words
+            .iter()
+            .map(|(word, value)| (word.iter().rev().copied().collect(), *value))
+            .collect();
+        Matcher {
+            forward: Automaton::new(words.iter().map(|(word, value)| (&word[..], *value))),
+            backward: Automaton::new(reversed.iter().map(|(word, value)| (&word[..], *value))),
+            longest: words.iter().map(|(word, _)| word.len()).max().unwrap_or(1),
+        }
+    }
+
+    // The number starting the earliest.
+    pub fn first(&self, line: &str) -> Option<Token> {
+        let chars: Vec<(usize, char)> = line.char_indices().collect();
+        let found = self.leftmost(&self.forward, chars.iter().map(|(_, c)| *c))?;
+        Some(Self::token(line, &chars, found))
+    }
+
+    // The number ending the latest.
+    pub fn last(&self, line: &str) -> Option<Token> {
+        let chars: Vec<(usize, char)> = line.char_indices().collect();
+        let found = self.leftmost(&self.backward, chars.iter().rev().map(|(_, c)| *c))?;
+        let count = chars.len();
+        Some(Self::token(
+            line,
+            &chars,
+            Match {
+                start: count - found.end,
+                end: count - found.start,
+                ..found
+            },
+        ))
+    }
+
+    // The match starting the earliest in the sequence, the longest one on a tie. Digits
+    // always match on their own.
+    fn leftmost(&self, automaton: &Automaton, chars: impl Iterator<Item = char>) -> Option<Match> {
+        let mut best: Option<Match> = None;
+        let mut state = 0;
+        for (index, c) in chars.enumerate() {
+            // Later matches can't start before the best one anymore
+            if matches!(best, Some(found) if index >= found.start + self.longest) {
+                break;
+            }
+            state = automaton.step(state, fold(c));
+            let digit = c
+                .to_digit(10)
+                .map(|digit| (1, digit as u8, TokenKind::Digit));
+            let words = automaton.nodes[state]
+                .outputs
+                .iter()
+                .map(|(length, value)| (*length, *value, TokenKind::Word));
+            for (length, value, kind) in digit.into_iter().chain(words) {
+                let candidate = Match {
+                    start: index + 1 - length,
+                    end: index + 1,
+                    value,
+                    kind,
+                };
+                best = match best {
+                    Some(current)
+                        if current.start < candidate.start
+                            || (current.start == candidate.start
+                                && current.end >= candidate.end) =>
+                    {
+                        Some(current)
+                    }
+                    _ => Some(candidate),
+                };
+            }
+        }
+        best
+    }
+
+    fn token(line: &str, chars: &[(usize, char)], found: Match) -> Token {
+        Token {
+            value: found.value,
+            start: chars[found.start].0,
+            end: chars
+                .get(found.end)
+                .map_or(line.len(), |(offset, _)| *offset),
+            kind: found.kind,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(matcher: &Matcher, line: &str) -> (u8, u8) {
+        (
+            matcher.first(line).unwrap().value,
+            matcher.last(line).unwrap().value,
+        )
+    }
+
+    #[test]
+    fn digits_and_words() {
+        let matcher = Matcher::new(&Vocabulary::english());
+        assert_eq!(values(&matcher, "two1nine"), (2, 9));
+        assert_eq!(values(&matcher, "abcone2threexyz"), (1, 3));
+        assert_eq!(values(&matcher, "4nineeightseven2"), (4, 2));
+        assert_eq!(values(&matcher, "treb7uchet"), (7, 7));
+    }
+
+    #[test]
+    fn overlapping_words() {
+        let matcher = Matcher::new(&Vocabulary::english());
+        assert_eq!(values(&matcher, "eightwo"), (8, 2));
+        assert_eq!(values(&matcher, "oneight"), (1, 8));
+        assert_eq!(values(&matcher, "xtwone3four"), (2, 4));
+        assert_eq!(values(&matcher, "sevenine"), (7, 9));
+    }
+
+    #[test]
+    fn zero_is_not_a_word() {
+        let matcher = Matcher::new(&Vocabulary::english());
+        assert_eq!(matcher.first("zero"), None);
+        assert_eq!(values(&matcher, "zero5zero"), (5, 5));
+    }
+
+    #[test]
+    fn digits_only() {
+        let matcher = Matcher::new(&Vocabulary::default());
+        assert_eq!(values(&matcher, "one2three4five"), (2, 4));
+        assert_eq!(matcher.last("eight"), None);
+    }
+
+    #[test]
+    fn token_offsets() {
+        let matcher = Matcher::new(&Vocabulary::english());
+        assert_eq!(
+            matcher.first("ab3oneight"),
+            Some(Token {
+                value: 3,
+                start: 2,
+                end: 3,
+                kind: TokenKind::Digit
+            })
+        );
+        assert_eq!(
+            matcher.last("ab3oneight"),
+            Some(Token {
+                value: 8,
+                start: 5,
+                end: 10,
+                kind: TokenKind::Word
+            })
+        );
+        // Offsets are in bytes
+        assert_eq!(matcher.first("éone").map(|token| token.start), Some(2));
+    }
+
+    #[test]
+    fn longest_word_on_a_tie() {
+        let vocabulary = Vocabulary::new([("six", 6), ("sixty", 7)]);
+        let matcher = Matcher::new(&vocabulary);
+        assert_eq!(matcher.first("sixty"), matcher.last("sixty"));
+        assert_eq!(matcher.first("sixty").map(|token| token.value), Some(7));
+        assert_eq!(matcher.first("xsix").map(|token| token.value), Some(6));
+    }
+
+    #[test]
+    fn case_and_accents() {
+        let mut vocabulary = Vocabulary::german();
+        vocabulary.extend(Vocabulary::french());
+        let matcher = Matcher::new(&vocabulary);
+        assert_eq!(values(&matcher, "xFUNFyDeux"), (5, 2));
+        assert_eq!(values(&matcher, "fünfzehn"), (5, 5));
+        assert_eq!(values(&matcher, "trÖis"), (3, 3));
+        let token = matcher.last("Zwölf SÉPT").unwrap();
+        assert_eq!((token.value, token.start, token.end), (7, 7, 12));
+    }
+}
@@ -0,0 +1,262 @@
+use std::fmt::Display;
+use std::fs;
+use std::io;
+
+// Spelled-out numbers and the digit each one stands for.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Vocabulary {
+    words: Vec<(String, u8)>,
+}
+
+impl Vocabulary {
+    // Unchecked, for the built-in languages and the tests. Other words go through add_word.
+    pub(super) fn new<S: Into<String>>(words: impl IntoIterator<Item = (S, u8)>) -> Self {
+        Vocabulary {
+            words: words
+                .into_iter()
+                .map(|(word, value)| (word.into(), value))
+                .collect(),
+        }
+    }
+
+    // "one" to "nine", the puzzle doesn't count "zero" as a digit.
+    pub fn english() -> Self {
+        Vocabulary::new([
+            ("one", 1),
+            ("two", 2),
+            ("three", 3),
+            ("four", 4),
+            ("five", 5),
+            ("six", 6),
+            ("seven", 7),
+            ("eight", 8),
+            ("nine", 9),
+        ])
+    }
+
+    pub fn french() -> Self {
+        Vocabulary::new([
+            ("un", 1),
+            ("deux", 2),
+            ("trois", 3),
+            ("quatre", 4),
+            ("cinq", 5),
+            ("six", 6),
+            ("sept", 7),
+            ("huit", 8),
+            ("neuf", 9),
+        ])
+    }
+
+    pub fn german() -> Self {
+        Vocabulary::new([
+            ("eins", 1),
+            ("zwei", 2),
+            ("drei", 3),
+            ("vier", 4),
+            ("fünf", 5),
+            ("sechs", 6),
+            ("sieben", 7),
+            ("acht", 8),
+            ("neun", 9),
+        ])
+    }
+
+    pub fn spanish() -> Self {
+        Vocabulary::new([
+            ("uno", 1),
+            ("dos", 2),
+            ("tres", 3),
+            ("cuatro", 4),
+            ("cinco", 5),
+            ("seis", 6),
+            ("siete", 7),
+            ("ocho", 8),
+            ("nueve", 9),
+        ])
+    }
+
+    // The built-in vocabulary for an ISO 639-1 code.
+    pub fn for_language(code: &str) -> Option<Self> {
+        match code {
+            "en" => Some(Vocabulary::english()),
+            "fr" => Some(Vocabulary::french()),
+            "de" => Some(Vocabulary::german()),
+            "es" => Some(Vocabulary::spanish()),
+            _ => None,
+        }
+    }
+
+    pub fn extend(&mut self, other: Vocabulary) {
+        self.words.extend(other.words);
+    }
+
+    // An empty word would match everywhere, and a digit above 9 would break the two-digit
+    // calibration values.
+    pub fn add_word(&mut self, word: &str, digit: u8) -> Result<(), VocabularyError> {
+        if word.is_empty() || digit > 9 {
+            return Err(VocabularyError::InvalidWord {
+                word: word.to_string(),
+                digit,
+            });
+        }
+        self.words.push((word.to_string(), digit));
+        Ok(())
+    }
+
+    // A config has one `key = value` per line, # starts a comment. The `languages` key takes
+    // a comma separated list of built-in vocabularies, any other key is a word and its digit:
+    //
+    //   languages = en, fr
+    //   uno = 1
+    pub fn from_config(config: &str) -> Result<Self, VocabularyError> {
+        let mut vocabulary = Vocabulary::default();
+        for (index, line) in config.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let invalid = || VocabularyError::InvalidLine {
+                line_number,
+                content: line.to_string(),
+            };
+            let (key, value) = line.split_once('=').ok_or_else(invalid)?;
+            let (key, value) = (key.trim(), value.trim());
+            if key == "languages" {
+                for code in value.split(',').map(str::trim) {
+                    let language = Vocabulary::for_language(code).ok_or_else(|| {
+                        VocabularyError::UnknownLanguage {
+                            line_number,
+                            code: code.to_string(),
+                        }
+                    })?;
+                    vocabulary.extend(language);
+                }
+            } else {
+                let digit = value.parse::<u8>().map_err(|_| invalid())?;
+                vocabulary.add_word(key, digit).map_err(|_| invalid())?;
+            }
+        }
+        Ok(vocabulary)
+    }
+
+    pub fn load(filename: &str) -> Result<Self, VocabularyError> {
+        let config = fs::read_to_string(filename).map_err(VocabularyError::Io)?;
+        Vocabulary::from_config(&config)
+    }
+
+    pub fn words(&self) -> &[(String, u8)] {
+        &self.words
+    }
+}
+
+#[derive(Debug)]
+pub enum VocabularyError {
+    Io(io::Error),
+    InvalidLine { line_number: usize, content: String },
+    UnknownLanguage { line_number: usize, code: String },
+    InvalidWord { word: String, digit: u8 },
+}
+
+impl Display for VocabularyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VocabularyError::Io(err) => write!(f, "Can't read the vocabulary: {}", err),
+            VocabularyError::InvalidLine {
+                line_number,
+                content,
+            } => write!(
+                f,
+                "Invalid vocabulary line {}: {:?}, expected WORD = DIGIT",
+                line_number, content
+            ),
+            VocabularyError::UnknownLanguage { line_number, code } => write!(
+                f,
+                "Unknown language {:?} on vocabulary line {}, expected en, fr, de or es",
+                code, line_number
+            ),
+            VocabularyError::InvalidWord { word, digit } => write!(
+                f,
+                "Invalid word {:?} for {}, expected a non-empty word and a digit from 0 to 9",
+                word, digit
+            ),
+        }
+    }
+}
+
+impl std::error::Error for VocabularyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            VocabularyError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vocabulary_from_config() {
+        let config = "# Logs from the Geneva team\nlanguages = fr, de\n\n  uno = 1 # Ticino\n";
+        let vocabulary = Vocabulary::from_config(config).unwrap();
+        assert_eq!(vocabulary.words().len(), 19);
+        assert!(vocabulary.words().contains(&("fünf".to_string(), 5)));
+        assert_eq!(vocabulary.words().last(), Some(&("uno".to_string(), 1)));
+    }
+
+    #[test]
+    fn invalid_config() {
+        match Vocabulary::from_config("languages = en\nuno: 1") {
+            Err(VocabularyError::InvalidLine {
+                line_number: 2,
+                content,
+            }) => assert_eq!(content, "uno: 1"),
+            other => panic!("{:?}", other),
+        }
+        assert!(matches!(
+            Vocabulary::from_config("dix = 10"),
+            Err(VocabularyError::InvalidLine { line_number: 1, .. })
+        ));
+        match Vocabulary::from_config("languages = en, it") {
+            Err(VocabularyError::UnknownLanguage {
+                line_number: 1,
+                code,
+            }) => assert_eq!(code, "it"),
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn invalid_words() {
+        let mut vocabulary = Vocabulary::default();
+        assert!(matches!(
+            vocabulary.add_word("", 5),
+            Err(VocabularyError::InvalidWord { digit: 5, .. })
+        ));
+        assert!(matches!(
+            vocabulary.add_word("dix", 10),
+            Err(VocabularyError::InvalidWord { digit: 10, .. })
+        ));
+        assert!(vocabulary.words().is_empty());
+        vocabulary.add_word("uno", 1).unwrap();
+        assert_eq!(vocabulary.words(), &[("uno".to_string(), 1)]);
+        assert!(matches!(
+            Vocabulary::from_config(" = 5"),
+            Err(VocabularyError::InvalidLine { line_number: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn built_in_languages() {
+        for code in ["en", "fr", "de", "es"] {
+            let vocabulary = Vocabulary::for_language(code).unwrap();
+            let mut digits: Vec<u8> = vocabulary.words().iter().map(|(_, d)| *d).collect();
+            digits.sort();
+            assert_eq!(digits, (1..=9).collect::<Vec<u8>>());
+        }
+        assert_eq!(Vocabulary::for_language("it"), None);
+    }
+}
@@ -1,81 +1,113 @@
-mod calibration_reader {
-    use phf::phf_map;
-    use std::fs;
-    use std::io::BufRead;
-    use std::io::BufReader;
+mod calibration_reader;
 
-    const NUMBERS: phf::Map<&str, char> = phf_map! {
-        "zero" => '0',
-        "one" => '1',
-        "two" => '2',
-        "three" => '3',
-        "four" => '4',
-        "five" => '5',
-        "six" => '6',
-        "seven" => '7',
-        "eight" => '8',
-        "nine" => '9',
-    };
-    pub fn get_calibration(filename: &str) -> u32 {
-        let document_file = open_calibration_document(filename).unwrap();
-        let mut result = 0u32;
-        for line in get_line_iter(document_file) {
-            let number = extract_number_from_string(line);
-            result += number as u32;
-        }
-        result
-    }
+use crate::calibration_reader::{
+    get_calibration, get_calibration_audit, get_calibration_skipping_invalid, render_json,
+    render_table, CalibrationMode, Vocabulary,
+};
+use std::env;
+use std::io;
 
-    fn open_calibration_document(filename: &str) -> std::io::Result<BufReader<fs::File>> {
-        let file = fs::File::open(filename)?;
-        Ok(BufReader::new(file))
-    }
+const INPUT: &str = "adventofcode.com_2023_day_1_input.txt";
 
-    fn get_line_iter(buffer: impl BufRead) -> impl Iterator<Item = String> {
-        buffer.lines().map(|l| l.unwrap())
+fn run(name: &str, mode: &CalibrationMode, skip_invalid: bool) {
+    if skip_invalid {
+        match get_calibration_skipping_invalid(INPUT, mode) {
+            Ok(summary) => println!("The result for {} is {}", name, summary),
+            Err(err) => eprintln!("The calibration for {} failed: {}", name, err),
+        }
+    } else {
+        match get_calibration(INPUT, mode) {
+            Ok(result) => println!("The result for {} is {}.", name, result),
+            Err(err) => eprintln!("The calibration for {} failed: {}", name, err),
+        }
     }
+}
 
-    fn extract_number_from_string(string: String) -> u8 {
-        let mut res_buf = String::new();
-        let mut number_buf = String::new();
-        // forward
-        'outer: for chr in string.chars() {
-            if chr.is_ascii_digit() {
-                res_buf.push(chr);
-                break;
-            } else {
-                number_buf.push(chr);
-                for key in NUMBERS.keys() {
-                    if number_buf.contains(key) {
-                        res_buf.push(NUMBERS[key]);
-                        break 'outer;
+// A custom vocabulary, computed after both parts, is built from:
+// - `--config FILE` for a vocabulary file, see vocabulary.conf
+// - `--languages en,fr,de,es` for built-in vocabularies
+// - `WORD=DIGIT` for single words
+// With `--skip-invalid` lines without a number are left out and listed instead of failing.
+// With `--audit table|json` the tokens read on each line are printed instead of the results,
+// for the custom vocabulary or English by default.
+fn main() {
+    let mut vocabulary = Vocabulary::default();
+    let mut skip_invalid = false;
+    let mut audit_format = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--skip-invalid" => skip_invalid = true,
+            "--audit" => audit_format = args.next(),
+            "--config" => match args.next().map(|file| Vocabulary::load(&file)) {
+                Some(Ok(loaded)) => vocabulary.extend(loaded),
+                Some(Err(err)) => {
+                    eprintln!("{}", err);
+                    return;
+                }
+                None => {
+                    eprintln!("--config expects a file");
+                    return;
+                }
+            },
+            "--languages" => {
+                for code in args.next().unwrap_or_default().split(',') {
+                    match Vocabulary::for_language(code) {
+                        Some(language) => vocabulary.extend(language),
+                        None => {
+                            eprintln!("Unknown language {:?}, expected en, fr, de or es", code);
+                            return;
+                        }
                     }
                 }
             }
-        }
-        // backward
-        number_buf.clear();
-        'outer: for chr in string.chars().rev() {
-            if chr.is_ascii_digit() {
-                res_buf.push(chr);
-                break;
-            } else {
-                number_buf.insert(0, chr);
-                for key in NUMBERS.keys() {
-                    if number_buf.contains(key) {
-                        res_buf.push(NUMBERS[key]);
-                        break 'outer;
+            _ => match arg
+                .split_once('=')
+                .map(|(word, value)| (word, value.parse::<u8>()))
+            {
+                Some((word, Ok(value))) => {
+                    if let Err(err) = vocabulary.add_word(word, value) {
+                        eprintln!("{}", err);
+                        return;
                     }
                 }
+                _ => {
+                    eprintln!("Invalid word {}, expected WORD=DIGIT", arg);
+                    return;
+                }
+            },
+        }
+    }
+    if let Some(format) = audit_format {
+        let mode = if vocabulary == Vocabulary::default() {
+            CalibrationMode::English
+        } else {
+            CalibrationMode::Custom(vocabulary)
+        };
+        let audits = match get_calibration_audit(INPUT, &mode) {
+            Ok(audits) => audits,
+            Err(err) => {
+                eprintln!("The audit failed: {}", err);
+                return;
+            }
+        };
+        let result = match format.as_str() {
+            "table" => render_table(&audits, &mut io::stdout()),
+            "json" => render_json(&audits, &mut io::stdout()),
+            other => {
+                eprintln!("Unknown audit format {}, expected table or json", other);
+                return;
             }
+        };
+        if let Err(err) = result {
+            eprintln!("{}", err);
         }
-        // Conversion and return
-        res_buf.parse().unwrap()
+        return;
+    }
+    run("part 1", &CalibrationMode::DigitsOnly, skip_invalid);
+    run("part 2", &CalibrationMode::English, skip_invalid);
+    if vocabulary != Vocabulary::default() {
+        let mode = CalibrationMode::Custom(vocabulary);
+        run("the custom vocabulary", &mode, skip_invalid);
     }
-}
-
-use crate::calibration_reader::get_calibration;
-fn main() {
-    let result = get_calibration("adventofcode.com_2023_day_1_input.txt");
-    println!("The result is {}.", result);
 }
@@ -0,0 +1,205 @@
+use crate::{ColorCount, GameTally};
+use std::collections::{BTreeMap, BTreeSet};
+
+// How the size of a bag is compared.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BagSize {
+    // The number of cubes
+    Total,
+    // ColorCount::power, the number of cubes on a tie. A bag with a color at 0 cubes has a
+    // power of 0, candidate_bags leaves such colors out.
+    Power,
+}
+
+impl BagSize {
+    fn key(&self, bag: &ColorCount) -> (u32, u32) {
+        match self {
+            BagSize::Total => (bag.total(), 0),
+            BagSize::Power => (bag.power(), bag.total()),
+        }
+    }
+}
+
+// A bag and the games that could have been played with it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BagConsistency {
+    pub bag: ColorCount,
+    pub game_ids: Vec<u32>,
+}
+
+impl GameTally {
+    pub fn consistency(&self, bags: &[ColorCount]) -> Vec<BagConsistency> {
+        bags.iter()
+            .map(|bag| BagConsistency {
+                bag: bag.clone(),
+                game_ids: self
+                    .games
+                    .iter()
+                    .filter(|game| game.is_possible(bag))
+                    .map(|game| game.id)
+                    .collect(),
+            })
+            .collect()
+    }
+
+    // Every combination of the counts the minimum sets of the games have for each color,
+    // 0 included. The smallest bag for any number of games is one of them: lowering a count
+    // to the next one in the list doesn't make any game impossible. A color with 0 cubes is
+    // left out of the bag. There are as many bags as the product of the number of counts per
+    // color, so it gets big with many colors.
+    pub fn candidate_bags(&self) -> Vec<ColorCount> {
+        let mut counts: BTreeMap<String, BTreeSet<u8>> = BTreeMap::new();
+        let minimum_sets: Vec<ColorCount> = self
+            .games
+            .iter()
+            .map(|game| game.minimum_set_of_cubes(&[]))
+            .collect();
+        for set in &minimum_sets {
+            for (color, _) in set.iter() {
+                counts.entry(color.to_string()).or_default();
+            }
+        }
+        for (color, values) in counts.iter_mut() {
+            values.extend(minimum_sets.iter().map(|set| set.get(color)));
+        }
+        let mut bags = vec![ColorCount::default()];
+        for (color, values) in &counts {
+            bags = bags
+                .iter()
+                .flat_map(|bag| {
+                    values.iter().map(move |count| {
+                        let mut bag = bag.clone();
+                        if *count > 0 {
+                            bag.set(color.as_str(), *count);
+                        }
+                        bag
+                    })
+                })
+                .collect();
+        }
+        bags
+    }
+
+    // The smallest of the candidate bags possible for at least the fraction of the games,
+    // 1.0 for all of them. The first one on a tie.
+    pub fn smallest_bag(
+        &self,
+        candidates: &[ColorCount],
+        size: BagSize,
+        fraction: f64,
+    ) -> Option<BagConsistency> {
+        let count = self.games.len() as f64;
+        self.consistency(candidates)
+            .into_iter()
+            .filter(|found| {
+                self.games.is_empty() || found.game_ids.len() as f64 / count >= fraction
+            })
+            .min_by_key(|found| size.key(&found.bag))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tally() -> GameTally {
+        let lines = [
+            "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green",
+            "Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue",
+            "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red",
+            "Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red",
+            "Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green",
+        ];
+        GameTally::parse_lines(lines.iter().map(|line| line.to_string())).unwrap()
+    }
+
+    fn bag(cubes: &str) -> ColorCount {
+        cubes.parse().unwrap()
+    }
+
+    #[test]
+    fn games_per_bag() {
+        let bags = [
+            bag("12 red, 13 green, 14 blue"),
+            bag("6 red, 3 green, 6 blue"),
+        ];
+        let found = tally().consistency(&bags);
+        assert_eq!(found[0].game_ids, vec![1, 2, 5]);
+        assert_eq!(found[1].game_ids, vec![1, 2, 5]);
+    }
+
+    #[test]
+    fn smallest_bag_for_all_games() {
+        let tally = tally();
+        let candidates = tally.candidate_bags();
+        let found = tally
+            .smallest_bag(&candidates, BagSize::Total, 1.0)
+            .unwrap();
+        assert_eq!(found.bag, bag("20 red, 13 green, 15 blue"));
+        assert_eq!(found.game_ids.len(), 5);
+        let found = tally
+            .smallest_bag(&candidates, BagSize::Power, 1.0)
+            .unwrap();
+        assert_eq!(found.bag, bag("20 red, 13 green, 15 blue"));
+    }
+
+    #[test]
+    fn smallest_bag_for_a_fraction() {
+        let tally = tally();
+        let candidates = tally.candidate_bags();
+        let found = tally
+            .smallest_bag(&candidates, BagSize::Total, 0.6)
+            .unwrap();
+        assert_eq!(found.bag, bag("6 red, 3 green, 6 blue"));
+        assert_eq!(found.game_ids, vec![1, 2, 5]);
+        let found = tally
+            .smallest_bag(&candidates, BagSize::Total, 0.8)
+            .unwrap();
+        assert_eq!(found.bag, bag("14 red, 3 green, 15 blue"));
+        assert_eq!(found.game_ids, vec![1, 2, 4, 5]);
+        let found = tally
+            .smallest_bag(&candidates, BagSize::Power, 0.2)
+            .unwrap();
+        assert_eq!(found.bag, bag("1 red, 3 green, 4 blue"));
+        assert_eq!(found.game_ids, vec![2]);
+    }
+
+    #[test]
+    fn no_candidate_is_big_enough() {
+        let tally = tally();
+        let found = tally.smallest_bag(&[bag("1 red")], BagSize::Total, 0.5);
+        assert_eq!(found, None);
+    }
+
+    #[test]
+    fn fraction_without_rounding_error() {
+        let lines = (1..=100).map(|id| format!("Game {}: {} red", id, id));
+        let tally = GameTally::parse_lines(lines).unwrap();
+        let candidates = tally.candidate_bags();
+        let found = tally
+            .smallest_bag(&candidates, BagSize::Total, 0.07)
+            .unwrap();
+        assert_eq!(found.bag, bag("7 red"));
+        assert_eq!(found.game_ids.len(), 7);
+        // One game short
+        let found = tally.smallest_bag(&[bag("6 red")], BagSize::Total, 0.07);
+        assert_eq!(found, None);
+    }
+
+    #[test]
+    fn missing_colors_dont_win_on_power() {
+        let lines = ["Game 1: 2 red, 3 blue", "Game 2: 4 green, 1 red, 0 blue"];
+        let tally = GameTally::parse_lines(lines.iter().map(|line| line.to_string())).unwrap();
+        let candidates = tally.candidate_bags();
+        assert!(candidates
+            .iter()
+            .all(|candidate| candidate.iter().all(|(_, count)| count > 0)));
+        let found = tally
+            .smallest_bag(&candidates, BagSize::Power, 0.5)
+            .unwrap();
+        assert_eq!(found.bag, bag("1 red, 4 green"));
+        assert_eq!(found.game_ids, vec![2]);
+        // The same power as the one of the minimum sets
+        assert_eq!(found.bag.power(), 4);
+    }
+}
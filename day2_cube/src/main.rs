@@ -1,109 +1,218 @@
+mod inference;
+
+use inference::BagSize;
 use regex::Regex;
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt::Display;
+use std::ops::Range;
+use std::process::ExitCode;
 use std::str::FromStr;
 use std::fs;
 use std::io::BufReader;
 use std::io::BufRead;
+use std::io;
 use once_cell::sync::Lazy;
 
-#[derive(Default)]
+#[derive(Default, Debug)]
 struct GameTally {
     games: Vec<Game>,
 }
 
 #[derive(Default, Debug)]
 struct Game {
-    id: u8,
+    id: u32,
     draws: Vec<ColorCount>,
 }
 
-#[derive(Default, Debug)]
+// A number of cubes per color, a color that isn't there counts as 0.
+#[derive(Default, Debug, Clone, PartialEq)]
 struct ColorCount {
-    red: u8,
-    blue: u8,
-    green: u8,
+    counts: BTreeMap<String, u8>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ParseErrorKind {
+    // The line doesn't start with "Game <id>:"
+    MissingHeader,
+    // The id doesn't fit in a u32
+    InvalidGameId,
+    // A cube isn't written "<count> <color>"
+    InvalidCube,
+    // The count doesn't fit in a u8
+    InvalidCount,
+}
+
+// Where a game record is wrong. The line and column start at 1, the column counts chars.
+// There's no line number when a lone record or bag is parsed.
+#[derive(Debug, Clone, PartialEq)]
 struct ParseError {
-    reason: String,
+    kind: ParseErrorKind,
+    line_number: Option<usize>,
+    column: usize,
+    token: String,
+}
+
+impl ParseError {
+    fn new(
+        kind: ParseErrorKind,
+        line: &str,
+        line_number: Option<usize>,
+        start: usize,
+        token: &str,
+    ) -> Self {
+        ParseError {
+            kind,
+            line_number,
+            column: line[..start].chars().count() + 1,
+            token: token.to_string(),
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reason = match self.kind {
+            ParseErrorKind::MissingHeader => "expected \"Game <id>:\" but found",
+            ParseErrorKind::InvalidGameId => "invalid game id",
+            ParseErrorKind::InvalidCube => "expected \"<count> <color>\" but found",
+            ParseErrorKind::InvalidCount => "invalid cube count",
+        };
+        if let Some(line_number) = self.line_number {
+            write!(f, "line {}, ", line_number)?;
+        }
+        write!(f, "column {}: {} {:?}", self.column, reason, self.token)
+    }
 }
 
+impl std::error::Error for ParseError {}
+
 impl FromStr for Game {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Self::parse(s)
+        Self::parse(s, None)
     }
 }
 
 impl Game {
-    fn parse(s: &str) -> Result<Self, ParseError> {
-        let id = Self::extract_game_id(s)?;
-        let draws = Self::extract_draws(s)?;
-
-        Ok(Game { id, draws })
-    }
-    fn extract_game_id(s: &str) -> Result<u8, ParseError> {
-        static regex: Lazy<Regex> = Lazy::new(|| Regex::new(r"^Game (?P<game_id>\d+):").unwrap());
-        let result = regex.captures(s).ok_or(ParseError {
-            reason: "The regex didn't match Game Id :-/".to_string(),
+    fn parse(s: &str, line_number: Option<usize>) -> Result<Self, ParseError> {
+        static HEADER: Lazy<Regex> = Lazy::new(|| Regex::new(r"^Game (\d+):").unwrap());
+        let header = HEADER.captures(s).ok_or_else(|| {
+            let token = s.split(':').next().unwrap_or_default();
+            ParseError::new(ParseErrorKind::MissingHeader, s, line_number, 0, token)
         })?;
-        let game_id = result.name("game_id").ok_or(ParseError {
-            reason: "Can't extract the game id from the regex".to_string(),
+        let game_id = header.get(1).unwrap();
+        let id = game_id.as_str().parse().map_err(|_| {
+            let kind = ParseErrorKind::InvalidGameId;
+            ParseError::new(kind, s, line_number, game_id.start(), game_id.as_str())
         })?;
-        game_id.as_str().parse().or(Err(ParseError {
-            reason: "Can't convert game_id to u8".to_string(),
-        }))
+        // The draws are separated by ';'
+        let mut draws = Vec::new();
+        let mut start = header.get(0).unwrap().end();
+        for draw in s[start..].split(';') {
+            draws.push(ColorCount::parse(s, start..start + draw.len(), line_number)?);
+            start += draw.len() + 1;
+        }
+        Ok(Game { id, draws })
     }
 
-    fn extract_draws(s: &str) -> Result<Vec<ColorCount>, ParseError> {
-        let mut result = Vec::new();
-        // Extract the color tallies
-        static regex: Lazy<Regex> = Lazy::new(|| Regex::new(r"Game \d+: (.*)").unwrap());
-        let caps = regex.captures(s).ok_or(ParseError {
-            reason: "The regex didn't catch the tallies :-/".to_string(),
-        })?;
-        let tallies = caps.get(1).unwrap().as_str();
-        // parse each tally
-        static regex2: Lazy<Regex> = Lazy::new(|| Regex::new(r"[^:;]+").unwrap());
-        for tally in regex2.find_iter(tallies).map(|m| m.as_str()) {
-            result.push(tally.parse()?);
-        }
-        Ok(result)
+    fn is_possible(&self, max_color: &ColorCount) -> bool {
+        self.draws.iter().all(|draw| draw.fits_in(max_color))
     }
-    fn is_possible(self: &Self, max_color: &ColorCount) -> bool {
+
+    // The smallest bag the game could have been played with, holding the given colors and
+    // every color drawn. A given color that was never drawn has 0 cubes, so the power of the
+    // bag is 0, as in the puzzle where a bag always holds red, green and blue cubes.
+    fn minimum_set_of_cubes(&self, colors: &[&str]) -> ColorCount {
+        let mut result = ColorCount::new(colors.iter().map(|color| (*color, 0)));
         for draw in &self.draws {
-            if draw.red > max_color.red {
-                return false;
-            }
-            if draw.green > max_color.green {
-                return false;
-            }
-            if draw.blue > max_color.blue {
-                return false;
+            for (color, count) in draw.iter() {
+                let most = result.counts.entry(color.to_string()).or_insert(0);
+                *most = (*most).max(count);
             }
         }
-        true
+        result
+    }
+
+    fn minimum_power(&self, colors: &[&str]) -> u32 {
+        self.minimum_set_of_cubes(colors).power()
     }
+}
 
-    fn minimum_set_of_cubes(self: &Self) -> ColorCount {
+impl ColorCount {
+    fn new<S: Into<String>>(counts: impl IntoIterator<Item = (S, u8)>) -> Self {
         let mut result = ColorCount::default();
-        for draw in &self.draws {
-            if draw.red > result.red { result.red = draw.red; }
-            if draw.green > result.green { result.green = draw.green; }
-            if draw.blue > result.blue { result.blue = draw.blue; }
+        for (color, count) in counts {
+            result.set(color, count);
         }
         result
     }
 
-    fn minimum_power(self: &Self) -> u32 {
-        self.minimum_set_of_cubes().power()
+    fn get(&self, color: &str) -> u8 {
+        self.counts.get(color).copied().unwrap_or(0)
+    }
+
+    fn set(&mut self, color: impl Into<String>, count: u8) {
+        self.counts.insert(color.into(), count);
+    }
+
+    // The colors by name with their count.
+    fn iter(&self) -> impl Iterator<Item = (&str, u8)> {
+        self.counts
+            .iter()
+            .map(|(color, count)| (color.as_str(), *count))
+    }
+
+    // No color has more cubes than in the other count.
+    fn fits_in(&self, other: &ColorCount) -> bool {
+        self.iter().all(|(color, count)| count <= other.get(color))
+    }
+
+    fn total(&self) -> u32 {
+        self.iter().map(|(_, count)| count as u32).sum()
+    }
+
+    // The product of the counts of the colors it has.
+    fn power(&self) -> u32 {
+        self.iter().map(|(_, count)| count as u32).product()
+    }
+
+    // Parses the cubes in a range of the line, separated by ','.
+    fn parse(
+        line: &str,
+        range: Range<usize>,
+        line_number: Option<usize>,
+    ) -> Result<Self, ParseError> {
+        static CUBE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(\d+)\s+(\w+)$").unwrap());
+        let mut result = ColorCount::default();
+        let mut start = range.start;
+        for cube in line[range].split(',') {
+            let trimmed = cube.trim();
+            let offset = start + cube.len() - cube.trim_start().len();
+            let caps = CUBE.captures(trimmed).ok_or_else(|| {
+                ParseError::new(ParseErrorKind::InvalidCube, line, line_number, offset, trimmed)
+            })?;
+            let (count, color) = (caps.get(1).unwrap(), caps.get(2).unwrap());
+            let count_value = count.as_str().parse().map_err(|_| {
+                let kind = ParseErrorKind::InvalidCount;
+                ParseError::new(kind, line, line_number, offset, count.as_str())
+            })?;
+            result.set(color.as_str(), count_value);
+            start += cube.len() + 1;
+        }
+        Ok(result)
     }
 }
 
-impl ColorCount {
-    fn power(self: &Self) -> u32 {
-        self.red as u32 * self.green as u32 * self.blue as u32
+// Written the way it is parsed, e.g. "14 blue, 13 green, 12 red".
+impl Display for ColorCount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let cubes: Vec<String> = self
+            .iter()
+            .map(|(color, count)| format!("{} {}", count, color))
+            .collect();
+        write!(f, "{}", cubes.join(", "))
     }
 }
 
@@ -111,69 +220,150 @@ impl FromStr for ColorCount {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut result = ColorCount::default();
-        static regex: Lazy<Regex> = Lazy::new(|| Regex::new(r"(\d+) (\w+)(?:, )?").unwrap());
-        for (_, [count, color]) in regex.captures_iter(s).map(|c| c.extract()) {
-            let count = count.parse().or(Err(ParseError {
-                reason: "Can't convert color count to u8".to_string(),
-            }))?;
-            match color {
-                "red" => result.red = count,
-                "green" => result.green = count,
-                "blue" => result.blue = count,
-                _ => {
-                    return Err(ParseError {
-                        reason: "Unknown color".to_string(),
-                    })
-                }
-            }
-        }
-        Ok(result)
+        ColorCount::parse(s, 0..s.len(), None)
     }
 }
 
 impl GameTally {
-    fn push(self: &mut Self, game: Game) {
+    // Parses a game log, stopping at the first invalid record.
+    fn parse_lines(lines: impl Iterator<Item = String>) -> Result<Self, ParseError> {
+        let mut tally = GameTally::default();
+        for (index, line) in lines.enumerate() {
+            tally.push(Game::parse(&line, Some(index + 1))?);
+        }
+        Ok(tally)
+    }
+
+    // Parses every valid record of a game log, and returns the errors of the others.
+    fn parse_lines_lenient(lines: impl Iterator<Item = String>) -> (Self, Vec<ParseError>) {
+        let mut tally = GameTally::default();
+        let mut errors = Vec::new();
+        for (index, line) in lines.enumerate() {
+            match Game::parse(&line, Some(index + 1)) {
+                Ok(game) => tally.push(game),
+                Err(err) => errors.push(err),
+            }
+        }
+        (tally, errors)
+    }
+
+    fn push(&mut self, game: Game) {
         self.games.push(game);
     }
 
-    fn sum_possible_game_id(self: &Self, max_color: &ColorCount) -> u32 {
+    fn sum_possible_game_id(&self, max_color: &ColorCount) -> u32 {
         let mut result = 0;
         for game in &self.games {
             if game.is_possible(max_color) {
-                result += game.id as u32;
+                result += game.id;
             }
         }
         result
     }
 
-    fn sum_of_minimum_power(self: &Self) -> u32 {
-        self.games.iter().map(|g| g.minimum_power()).sum()
+    // With the colors every bag holds, see Game::minimum_set_of_cubes.
+    fn sum_of_minimum_power(&self, colors: &[&str]) -> u32 {
+        self.games.iter().map(|g| g.minimum_power(colors)).sum()
     }
 }
 
-fn get_lines_from_files(filename: &str) -> impl Iterator<Item=String> {
-    let file = fs::File::open(filename).unwrap();
+fn get_lines_from_files(filename: &str) -> io::Result<Vec<String>> {
+    let file = fs::File::open(filename)?;
     let file_buffer = BufReader::new(file);
-    file_buffer.lines().map(|l| l.unwrap())
+    file_buffer.lines().collect()
 }
 
-fn main() -> Result<(), ParseError> {
-    let mut game_tally = GameTally::default();
-    for line in get_lines_from_files("input.txt") {
-        game_tally.push(line.parse()?);
+// The bag can be given as an argument, e.g. "12 red, 4 yellow, 7 purple". With
+// `--lenient` the invalid records are listed and left out instead of stopping. With
+// `--infer total|power [--fraction F]` the smallest bag for all the games, or for a
+// fraction of them, is searched too.
+fn main() -> ExitCode {
+    let mut lenient = false;
+    let mut bag = None;
+    let mut infer = None;
+    let mut fraction = 1.0;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--lenient" => lenient = true,
+            "--infer" => match args.next().as_deref() {
+                Some("total") => infer = Some(BagSize::Total),
+                Some("power") => infer = Some(BagSize::Power),
+                other => {
+                    eprintln!("Unknown bag size {:?}, expected total or power", other);
+                    return ExitCode::FAILURE;
+                }
+            },
+            "--fraction" => match args.next().and_then(|f| f.parse().ok()) {
+                Some(f) if (0.0..=1.0).contains(&f) => fraction = f,
+                _ => {
+                    eprintln!("The fraction must be a number between 0 and 1");
+                    return ExitCode::FAILURE;
+                }
+            },
+            _ => bag = Some(arg),
+        }
     }
-    let max_color = ColorCount{red: 12, green: 13, blue: 14};
+    let lines = match get_lines_from_files("input.txt") {
+        Ok(lines) => lines.into_iter(),
+        Err(err) => {
+            eprintln!("Can't read input.txt: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+    let game_tally = if lenient {
+        let (game_tally, errors) = GameTally::parse_lines_lenient(lines);
+        for err in &errors {
+            eprintln!("{}", err);
+        }
+        if !errors.is_empty() {
+            eprintln!("{} invalid games left out", errors.len());
+        }
+        game_tally
+    } else {
+        match GameTally::parse_lines(lines) {
+            Ok(game_tally) => game_tally,
+            Err(err) => {
+                eprintln!("{}", err);
+                return ExitCode::FAILURE;
+            }
+        }
+    };
+    let max_color: ColorCount = match bag {
+        Some(bag) => match bag.parse() {
+            Ok(max_color) => max_color,
+            Err(err) => {
+                eprintln!("Invalid bag {:?}: {}", bag, err);
+                return ExitCode::FAILURE;
+            }
+        },
+        None => ColorCount::new([("red", 12), ("green", 13), ("blue", 14)]),
+    };
     let result = game_tally.sum_possible_game_id(&max_color);
     println!("The sum of possible game id: {}", result);
-    println!("The sum of minium powers: {}", game_tally.sum_of_minimum_power());
-    Ok(())
+    // The bags hold the colors of the given one
+    let colors: Vec<&str> = max_color.iter().map(|(color, _)| color).collect();
+    println!("The sum of minium powers: {}", game_tally.sum_of_minimum_power(&colors));
+    if let Some(size) = infer {
+        let candidates = game_tally.candidate_bags();
+        match game_tally.smallest_bag(&candidates, size, fraction) {
+            Some(found) => println!(
+                "The smallest bag is {}, possible for {} of {} games",
+                found.bag,
+                found.game_ids.len(),
+                game_tally.games.len()
+            ),
+            None => println!("No bag is possible for that many games"),
+        }
+    }
+    ExitCode::SUCCESS
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const RGB: [&str; 3] = ["red", "green", "blue"];
     const LINE: &str = "Game 1: 4 green, 7 blue; 2 blue, 4 red; 5 blue, 2 green, 2 red; 1 green, 3 red, 9 blue; 3 green, 9 blue; 7 green, 2 blue, 2 red";
     const LINE2: &str = "Game 2: 1 blue, 2 red; 1 green, 2 blue, 1 red; 1 red, 5 green; 3 red, 2 blue, 8 green; 3 blue, 2 red, 4 green; 2 blue, 4 green, 3 red";
     #[test]
@@ -187,9 +377,9 @@ mod tests {
     fn parse_single_draw() -> Result<(), ParseError> {
         let string = "7 green, 2 blue";
         let count: ColorCount = string.parse()?;
-        assert_eq!(count.red, 0);
-        assert_eq!(count.green, 7);
-        assert_eq!(count.blue, 2);
+        assert_eq!(count.get("red"), 0);
+        assert_eq!(count.get("green"), 7);
+        assert_eq!(count.get("blue"), 2);
         Ok(())
     }
 
@@ -202,7 +392,7 @@ mod tests {
 
     #[test]
     fn game_is_possible() -> Result<(), ParseError> {
-        let max = ColorCount { red: 25, green: 25, blue: 25};
+        let max = ColorCount::new([("red", 25), ("green", 25), ("blue", 25)]);
         let game: Game = LINE.parse()?;
         assert!(game.is_possible(&max));
         Ok(())
@@ -210,7 +400,7 @@ mod tests {
 
     #[test]
     fn game_is_not_possible() -> Result<(), ParseError> {
-        let max = ColorCount { red: 25, green: 3, blue: 25};
+        let max = ColorCount::new([("red", 25), ("green", 3), ("blue", 25)]);
         let game: Game = LINE.parse()?;
         assert!(!game.is_possible(&max));
         Ok(())
@@ -227,7 +417,7 @@ mod tests {
 
     #[test]
     fn game_tally_add_possible_game_id() -> Result<(), ParseError> {
-        let max = ColorCount { red: 25, green: 25, blue: 25};
+        let max = ColorCount::new([("red", 25), ("green", 25), ("blue", 25)]);
         let mut tally = GameTally::default();
         tally.push(LINE.parse()?);
         tally.push(LINE2.parse()?);
@@ -238,17 +428,120 @@ mod tests {
     #[test]
     fn game_minimum_set_of_cubes() -> Result<(), ParseError> {
         let game: Game = LINE.parse()?;
-        let min_set = game.minimum_set_of_cubes();
-        assert_eq!(min_set.red, 4);
-        assert_eq!(min_set.blue, 9);
-        assert_eq!(min_set.green, 7);
+        let min_set = game.minimum_set_of_cubes(&[]);
+        assert_eq!(min_set.get("red"), 4);
+        assert_eq!(min_set.get("blue"), 9);
+        assert_eq!(min_set.get("green"), 7);
         Ok(())
     }
 
     #[test]
     fn game_minimum_power() -> Result<(), ParseError> {
         let game: Game = LINE.parse()?;
-        assert_eq!(game.minimum_power(), 4*9*7);
+        assert_eq!(game.minimum_power(&[]), 4*9*7);
         Ok(())
     }
+
+    #[test]
+    fn any_color() -> Result<(), ParseError> {
+        let game: Game = "Game 3: 2 yellow, 1 red; 5 purple, 3 yellow".parse()?;
+        let min_set = game.minimum_set_of_cubes(&[]);
+        assert_eq!(min_set, ColorCount::new([("yellow", 3), ("red", 1), ("purple", 5)]));
+        assert_eq!(min_set.power(), 15);
+        assert_eq!(game.minimum_power(&["yellow", "purple"]), 15);
+        // A bag of the puzzle has green and blue cubes, none of them was drawn
+        assert_eq!(game.minimum_power(&RGB), 0);
+        assert!(game.is_possible(&"3 yellow, 1 red, 5 purple, 2 blue".parse()?));
+        // No purple cube in the bag
+        assert!(!game.is_possible(&"12 red, 13 green, 14 blue".parse()?));
+        Ok(())
+    }
+
+    #[test]
+    fn missing_color_power() -> Result<(), ParseError> {
+        let game: Game = "Game 1: 3 red, 2 green".parse()?;
+        assert_eq!(game.minimum_set_of_cubes(&RGB).get("blue"), 0);
+        assert_eq!(game.minimum_power(&RGB), 0);
+        let explicit: Game = "Game 2: 3 red, 2 green, 0 blue".parse()?;
+        assert_eq!(explicit.minimum_set_of_cubes(&RGB), game.minimum_set_of_cubes(&RGB));
+        assert_eq!(explicit.minimum_power(&[]), 0);
+        let mut tally = GameTally::default();
+        tally.push(game);
+        tally.push(LINE.parse()?);
+        // The yellow cubes only count for the game they were drawn in
+        tally.push("Game 3: 1 red, 1 green, 1 blue, 2 yellow".parse()?);
+        let powers: u32 = tally.games.iter().map(|g| g.minimum_power(&RGB)).sum();
+        assert_eq!(powers, 4 * 9 * 7 + 2);
+        assert_eq!(tally.sum_of_minimum_power(&RGB), powers);
+        Ok(())
+    }
+
+    #[test]
+    fn wide_game_id() -> Result<(), ParseError> {
+        let game: Game = "Game 1000: 3 red".parse()?;
+        assert_eq!(game.id, 1000);
+        Ok(())
+    }
+
+    #[test]
+    fn located_errors() {
+        let error = |line: &str| line.parse::<Game>().unwrap_err();
+        assert_eq!(
+            error("Gmae 1: 3 red"),
+            ParseError {
+                kind: ParseErrorKind::MissingHeader,
+                line_number: None,
+                column: 1,
+                token: "Gmae 1".to_string(),
+            }
+        );
+        assert_eq!(
+            error("Game 99999999999: 3 red"),
+            ParseError {
+                kind: ParseErrorKind::InvalidGameId,
+                line_number: None,
+                column: 6,
+                token: "99999999999".to_string(),
+            }
+        );
+        assert_eq!(
+            error("Game 7: 3 red;  2 blue,  green"),
+            ParseError {
+                kind: ParseErrorKind::InvalidCube,
+                line_number: None,
+                column: 26,
+                token: "green".to_string(),
+            }
+        );
+        assert_eq!(
+            error("Game 7: 3 red; 300 blue"),
+            ParseError {
+                kind: ParseErrorKind::InvalidCount,
+                line_number: None,
+                column: 16,
+                token: "300".to_string(),
+            }
+        );
+        assert_eq!(
+            error("Game 7: 3 red; 300 blue").to_string(),
+            "column 16: invalid cube count \"300\""
+        );
+    }
+
+    #[test]
+    fn lenient_parsing() {
+        let lines = [LINE, "Game 2 3 red", LINE2, "Game 4: 1 blue;"];
+        let lines = || lines.iter().map(|line| line.to_string());
+        let (tally, errors) = GameTally::parse_lines_lenient(lines());
+        assert_eq!(tally.games.len(), 2);
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].line_number, Some(2));
+        assert_eq!(errors[0].kind, ParseErrorKind::MissingHeader);
+        assert_eq!((errors[1].line_number, errors[1].column), (Some(4), 16));
+        assert_eq!(
+            errors[1].to_string(),
+            "line 4, column 16: expected \"<count> <color>\" but found \"\""
+        );
+        assert_eq!(GameTally::parse_lines(lines()).unwrap_err(), errors[0]);
+    }
 }
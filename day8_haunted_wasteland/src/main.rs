@@ -1,10 +1,13 @@
 use once_cell::sync::Lazy;
 use regex::Regex;
+use std::env;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 
 mod map;
 
+const MAX_TRACE_STEPS: usize = 100_000;
+
 fn main() {
     static REGEX: Lazy<Regex> =
         Lazy::new(|| Regex::new(r"([A-Z]{3}) = \(([A-Z]{3}), ([A-Z]{3})\)").unwrap());
@@ -26,6 +29,47 @@ fn main() {
             matches.get(3).unwrap().as_str(),
         );
     }
+    // Export instead of solving: `dot|mermaid [--labels] [--trace NODE]`
+    let args: Vec<String> = env::args().skip(1).collect();
+    if let Some(format) = args.first() {
+        let traversal = match args
+            .iter()
+            .position(|arg| arg == "--trace")
+            .and_then(|index| args.get(index + 1))
+            .map(|source| map.traverse(source, 'Z', MAX_TRACE_STEPS))
+            .transpose()
+        {
+            Ok(traversal) => traversal,
+            Err(err) => {
+                eprintln!("Can't trace: {}", err);
+                return;
+            }
+        };
+        if let Some(traversal) = &traversal {
+            if traversal.reached_destination() {
+                eprintln!(
+                    "Trace reached a destination after {} steps",
+                    traversal.step_count()
+                );
+            } else {
+                eprintln!(
+                    "Trace stopped after {} steps without reaching a destination",
+                    traversal.step_count()
+                );
+            }
+        }
+        let options = map::ExportOptions {
+            edge_labels: args.iter().any(|arg| arg == "--labels"),
+            traversal: traversal.as_ref(),
+            ..Default::default()
+        };
+        match format.as_str() {
+            "dot" => print!("{}", map.to_dot(&options)),
+            "mermaid" => print!("{}", map.to_mermaid(&options)),
+            other => eprintln!("Unknown export format {}, expected dot or mermaid", other),
+        }
+        return;
+    }
     // Part 1
     let result = map.step_count("AAA", "ZZZ").unwrap();
     println!("The result for part 1 is {}", result);
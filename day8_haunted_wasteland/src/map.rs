@@ -1,7 +1,12 @@
 use num::integer::lcm;
 use std::collections::HashMap;
+use std::fmt::Display;
 
-#[derive(Debug)]
+mod export;
+
+pub use export::ExportOptions;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Direction {
     Left,
     Right,
@@ -23,12 +28,54 @@ impl Direction {
 #[derive(Debug)]
 pub struct ParseError;
 
+// A node name that isn't in the network.
+#[derive(Debug, PartialEq)]
+pub struct UnknownNodeError {
+    pub node: String,
+}
+
+impl Display for UnknownNodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Unknown node {}", self.node)
+    }
+}
+
+impl std::error::Error for UnknownNodeError {}
+
 #[derive(Debug)]
 struct Cell {
     left: String,
     right: String,
 }
 
+// Nodes visited while walking the network, with the steps at which they were reached.
+#[derive(Debug, Default)]
+pub struct Traversal {
+    visits: HashMap<String, Vec<usize>>,
+    step_count: usize,
+    reached_destination: bool,
+}
+
+impl Traversal {
+    fn visit(&mut self, node: &str, step: usize) {
+        self.visits.entry(node.to_string()).or_default().push(step);
+        self.step_count = step;
+    }
+
+    pub fn steps_at(&self, node: &str) -> Option<&[usize]> {
+        self.visits.get(node).map(Vec::as_slice)
+    }
+
+    pub fn step_count(&self) -> usize {
+        self.step_count
+    }
+
+    // false when the walk was stopped by its maximum number of steps.
+    pub fn reached_destination(&self) -> bool {
+        self.reached_destination
+    }
+}
+
 #[derive(Debug)]
 pub struct Map {
     directions: Vec<Direction>,
@@ -94,6 +141,34 @@ impl Map {
         Ok(result)
     }
 
+    // Walk from source until a node ending with dest_end_letter is reached, recording every
+    // visit. Stops after max_steps since a ghost path may never reach its destination.
+    pub fn traverse(
+        &self,
+        source: &str,
+        dest_end_letter: char,
+        max_steps: usize,
+    ) -> Result<Traversal, UnknownNodeError> {
+        let mut traversal = Traversal::default();
+        let mut current = source;
+        traversal.visit(current, 0);
+        for (count, direction) in self.directions.iter().cycle().take(max_steps).enumerate() {
+            let cell = self.tree.get(current).ok_or_else(|| UnknownNodeError {
+                node: current.to_string(),
+            })?;
+            current = match direction {
+                Direction::Left => cell.left.as_str(),
+                Direction::Right => cell.right.as_str(),
+            };
+            traversal.visit(current, count + 1);
+            if current.ends_with(dest_end_letter) {
+                traversal.reached_destination = true;
+                break;
+            }
+        }
+        Ok(traversal)
+    }
+
     // TODO make up your mind between cell and node
     fn ghost_step_one_path(&self, source_cell: &str, dest_end_letter: char) -> Result<usize, ()> {
         let mut current = source_cell;
@@ -0,0 +1,232 @@
+use super::{Map, Traversal};
+use std::fmt::Write;
+
+// Beyond that many visits of a node only the first ones are listed, with the total.
+const MAX_LISTED_STEPS: usize = 5;
+
+// How the network should be drawn. Source and destination nodes are recognized the same way
+// as in Map::ghost_step_count, by the last letter of their name.
+#[derive(Debug)]
+pub struct ExportOptions<'a> {
+    pub source_end_letter: char,
+    pub dest_end_letter: char,
+    pub edge_labels: bool,
+    pub traversal: Option<&'a Traversal>,
+}
+
+impl Default for ExportOptions<'_> {
+    fn default() -> Self {
+        Self {
+            source_end_letter: 'A',
+            dest_end_letter: 'Z',
+            edge_labels: false,
+            traversal: None,
+        }
+    }
+}
+
+impl ExportOptions<'_> {
+    fn is_source(&self, node: &str) -> bool {
+        node.ends_with(self.source_end_letter)
+    }
+
+    fn is_dest(&self, node: &str) -> bool {
+        node.ends_with(self.dest_end_letter)
+    }
+
+    fn visit_steps(&self, node: &str) -> Option<String> {
+        let steps = self.traversal?.steps_at(node)?;
+        let listed: Vec<String> = steps
+            .iter()
+            .take(MAX_LISTED_STEPS)
+            .map(usize::to_string)
+            .collect();
+        if steps.len() > MAX_LISTED_STEPS {
+            Some(format!("{}... {} visits", listed.join(", "), steps.len()))
+        } else {
+            Some(listed.join(", "))
+        }
+    }
+}
+
+impl Map {
+    // Sorted so the output is stable between runs despite the HashMap.
+    fn sorted_nodes(&self) -> Vec<&str> {
+        let mut nodes: Vec<&str> = self.tree.keys().map(String::as_str).collect();
+        nodes.sort_unstable();
+        nodes
+    }
+
+    pub fn to_dot(&self, options: &ExportOptions) -> String {
+        let mut result = String::from("digraph network {\n");
+        let nodes = self.sorted_nodes();
+        for node in &nodes {
+            let mut attributes: Vec<String> = Vec::new();
+            if options.is_source(node) {
+                attributes.push("style=filled, fillcolor=palegreen".to_string());
+            } else if options.is_dest(node) {
+                attributes.push("style=filled, fillcolor=lightcoral".to_string());
+            }
+            if let Some(steps) = options.visit_steps(node) {
+                attributes.push(format!("color=blue, penwidth=2, xlabel=\"{}\"", steps));
+            }
+            if !attributes.is_empty() {
+                writeln!(result, "    \"{}\" [{}];", node, attributes.join(", ")).unwrap();
+            }
+        }
+        for node in &nodes {
+            let cell = &self.tree[*node];
+            for (label, target) in [("L", &cell.left), ("R", &cell.right)] {
+                if options.edge_labels {
+                    writeln!(
+                        result,
+                        "    \"{}\" -> \"{}\" [label=\"{}\"];",
+                        node, target, label
+                    )
+                    .unwrap();
+                } else {
+                    writeln!(result, "    \"{}\" -> \"{}\";", node, target).unwrap();
+                }
+            }
+        }
+        result.push_str("}\n");
+        result
+    }
+
+    pub fn to_mermaid(&self, options: &ExportOptions) -> String {
+        let mut result = String::from("flowchart LR\n");
+        let nodes = self.sorted_nodes();
+        for node in &nodes {
+            match options.visit_steps(node) {
+                Some(steps) => writeln!(result, "    {}[\"{} ({})\"]", node, node, steps).unwrap(),
+                None => writeln!(result, "    {}[{}]", node, node).unwrap(),
+            }
+        }
+        for node in &nodes {
+            let cell = &self.tree[*node];
+            for (label, target) in [("L", &cell.left), ("R", &cell.right)] {
+                if options.edge_labels {
+                    writeln!(result, "    {} -->|{}| {}", node, label, target).unwrap();
+                } else {
+                    writeln!(result, "    {} --> {}", node, target).unwrap();
+                }
+            }
+        }
+        // "end" is a reserved word in Mermaid, hence the class names
+        result.push_str("    classDef source fill:#98fb98\n");
+        result.push_str("    classDef destination fill:#f08080\n");
+        result.push_str("    classDef visited stroke:#00f,stroke-width:3px\n");
+        let mut write_class = |class: &str, selected: Vec<&str>| {
+            if !selected.is_empty() {
+                writeln!(result, "    class {} {}", selected.join(","), class).unwrap();
+            }
+        };
+        let nodes = nodes.iter().copied();
+        write_class(
+            "source",
+            nodes.clone().filter(|n| options.is_source(n)).collect(),
+        );
+        write_class(
+            "destination",
+            nodes
+                .clone()
+                .filter(|n| !options.is_source(n) && options.is_dest(n))
+                .collect(),
+        );
+        write_class(
+            "visited",
+            nodes.filter(|n| options.visit_steps(n).is_some()).collect(),
+        );
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::{Direction, UnknownNodeError};
+
+    fn create_map() -> Map {
+        let mut map = Map::new(vec![Direction::Left, Direction::Right]);
+        map.add_cell("AAA", "BBB", "CCC");
+        map.add_cell("BBB", "AAA", "ZZZ");
+        map.add_cell("CCC", "CCC", "CCC");
+        map.add_cell("ZZZ", "ZZZ", "ZZZ");
+        map
+    }
+
+    #[test]
+    fn dot_highlights_source_and_destination() {
+        let dot = create_map().to_dot(&ExportOptions::default());
+        assert!(dot.starts_with("digraph network {\n"));
+        assert!(dot.contains("\"AAA\" [style=filled, fillcolor=palegreen];"));
+        assert!(dot.contains("\"ZZZ\" [style=filled, fillcolor=lightcoral];"));
+        assert!(dot.contains("\"AAA\" -> \"BBB\";"));
+        assert!(dot.contains("\"BBB\" -> \"ZZZ\";"));
+        assert!(!dot.contains("label"));
+    }
+
+    #[test]
+    fn dot_with_edge_labels_and_traversal() {
+        let map = create_map();
+        let traversal = map.traverse("AAA", 'Z', 10).unwrap();
+        let options = ExportOptions {
+            edge_labels: true,
+            traversal: Some(&traversal),
+            ..Default::default()
+        };
+        let dot = map.to_dot(&options);
+        assert!(dot.contains("\"AAA\" -> \"CCC\" [label=\"R\"];"));
+        assert!(dot.contains("\"BBB\" [color=blue, penwidth=2, xlabel=\"1\"];"));
+        assert!(!dot.contains("\"CCC\" [color=blue"));
+    }
+
+    #[test]
+    fn mermaid_export() {
+        let map = create_map();
+        let traversal = map.traverse("AAA", 'Z', 10).unwrap();
+        let options = ExportOptions {
+            edge_labels: true,
+            traversal: Some(&traversal),
+            ..Default::default()
+        };
+        let mermaid = map.to_mermaid(&options);
+        assert!(mermaid.starts_with("flowchart LR\n"));
+        assert!(mermaid.contains("    AAA[\"AAA (0)\"]\n"));
+        assert!(mermaid.contains("    CCC[CCC]\n"));
+        assert!(mermaid.contains("    BBB -->|R| ZZZ\n"));
+        assert!(mermaid.contains("    class AAA source\n"));
+        assert!(mermaid.contains("    class ZZZ destination\n"));
+        assert!(mermaid.contains("    class AAA,BBB,ZZZ visited\n"));
+    }
+
+    #[test]
+    fn traversal_end() {
+        let map = create_map();
+        let traversal = map.traverse("AAA", 'Z', 10).unwrap();
+        assert!(traversal.reached_destination());
+        assert_eq!(traversal.step_count(), 2);
+        let traversal = map.traverse("CCC", 'Z', 10).unwrap();
+        assert!(!traversal.reached_destination());
+        assert_eq!(traversal.step_count(), 10);
+        assert_eq!(
+            map.traverse("XYZ", 'Z', 10).unwrap_err(),
+            UnknownNodeError {
+                node: "XYZ".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn many_visits_are_summarized() {
+        let map = create_map();
+        let traversal = map.traverse("CCC", 'Z', 100_000).unwrap();
+        let options = ExportOptions {
+            traversal: Some(&traversal),
+            ..Default::default()
+        };
+        assert!(map.to_dot(&options).contains(
+            "\"CCC\" [color=blue, penwidth=2, xlabel=\"0, 1, 2, 3, 4... 100001 visits\"];"
+        ));
+    }
+}
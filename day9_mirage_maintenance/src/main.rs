@@ -1,5 +1,59 @@
 mod predictor;
 
+use std::io::{self, BufRead};
+
+// Read OASIS readings from stdin, whitespace separated, and print the predicted next value
+// after each one. An empty line starts a new sequence.
 fn main() {
-    println!("Hello, world!");
+    let stdin = io::stdin();
+    let mut predictor = predictor::Predictor::new();
+    for (line_number, line) in stdin.lock().lines().enumerate() {
+        let line = match line {
+            Ok(line) => line,
+            Err(err) => {
+                eprintln!("Line {}: can't read the readings: {}", line_number + 1, err);
+                return;
+            }
+        };
+        if line.trim().is_empty() {
+            predictor = predictor::Predictor::new();
+            continue;
+        }
+        for token in line.split_whitespace() {
+            let reading: i64 = match token.parse() {
+                Ok(reading) => reading,
+                Err(err) => {
+                    eprintln!(
+                        "Line {}: invalid reading {}: {}",
+                        line_number + 1,
+                        token,
+                        err
+                    );
+                    continue;
+                }
+            };
+            let prediction = predictor
+                .push(reading)
+                .and_then(|_| predictor.predict_next());
+            match prediction {
+                Ok(next) => println!(
+                    "reading #{} = {} -> next {}",
+                    predictor.reading_count(),
+                    reading,
+                    next
+                ),
+                Err(predictor::PredictorError::NotPolynomial) => println!(
+                    "reading #{} = {} -> next undetermined",
+                    predictor.reading_count(),
+                    reading
+                ),
+                Err(err) => eprintln!(
+                    "Line {}: can't predict after {}: {}",
+                    line_number + 1,
+                    reading,
+                    err
+                ),
+            }
+        }
+    }
 }
@@ -1,12 +1,162 @@
+use std::fmt::Display;
+
+// Streaming predictor: readings are pushed one at a time and only the last value of each
+// row of the difference table is kept. A push updates those edges in O(readings) instead of
+// recomputing the whole table.
+#[derive(Debug, Default)]
 pub struct Predictor {
-    data: Vec<i32>
+    edges: Vec<i64>,
 }
 
-impl Predictor {
-    pub fn new(data: Vec<i32>) -> Self {
-        Predictor{data}
+#[derive(Debug, Clone, PartialEq)]
+pub enum PredictorError {
+    NoReading,
+    Overflow,
+    // The differences don't reach an all-zero row yet
+    NotPolynomial,
+}
+
+impl Display for PredictorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PredictorError::NoReading => write!(f, "No reading to predict from"),
+            PredictorError::Overflow => write!(f, "Arithmetic overflow in the differences"),
+            PredictorError::NotPolynomial => write!(
+                f,
+                "The differences never reach an all-zero row, more readings are needed"
+            ),
+        }
     }
 }
 
+impl std::error::Error for PredictorError {}
+
+impl Predictor {
+    pub fn new() -> Self {
+        Predictor::default()
+    }
+
+    // The edges are only updated once every difference is known to fit, so a push that
+    // overflows leaves the predictor as it was.
+    pub fn push(&mut self, reading: i64) -> Result<(), PredictorError> {
+        let mut edge = reading;
+        for previous_edge in &self.edges {
+            edge = edge
+                .checked_sub(*previous_edge)
+                .ok_or(PredictorError::Overflow)?;
+        }
+        let mut edge = reading;
+        for previous_edge in self.edges.iter_mut() {
+            let difference = edge - *previous_edge;
+            *previous_edge = edge;
+            edge = difference;
+        }
+        self.edges.push(edge);
+        Ok(())
+    }
+
+    // The last edge is the single value of the last row of differences. The rows reach an
+    // all-zero one exactly when it is 0, as Sequence requires in advent2023, since every row
+    // after an all-zero row is all zero too.
+    pub fn predict_next(&self) -> Result<i64, PredictorError> {
+        match self.edges.last() {
+            None => return Err(PredictorError::NoReading),
+            Some(edge) if *edge != 0 => return Err(PredictorError::NotPolynomial),
+            Some(_) => (),
+        }
+        self.edges.iter().try_fold(0i64, |acc, edge| {
+            acc.checked_add(*edge).ok_or(PredictorError::Overflow)
+        })
+    }
+
+    pub fn reading_count(&self) -> usize {
+        self.edges.len()
+    }
+}
 
 #[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn predictor_from(readings: &[i64]) -> Predictor {
+        let mut predictor = Predictor::new();
+        for reading in readings {
+            predictor.push(*reading).unwrap();
+        }
+        predictor
+    }
+
+    #[test]
+    fn predict_without_reading() {
+        assert_eq!(
+            Predictor::new().predict_next(),
+            Err(PredictorError::NoReading)
+        );
+    }
+
+    #[test]
+    fn predict_after_each_reading() {
+        let mut predictor = Predictor::new();
+        let mut predictions = Vec::new();
+        for reading in [1, 3, 6, 10, 15, 21] {
+            predictor.push(reading).unwrap();
+            predictions.push(predictor.predict_next());
+        }
+        let undetermined = Err(PredictorError::NotPolynomial);
+        assert_eq!(
+            predictions,
+            vec![
+                undetermined.clone(),
+                undetermined.clone(),
+                undetermined,
+                Ok(15),
+                Ok(21),
+                Ok(28)
+            ]
+        );
+        assert_eq!(predictor.reading_count(), 6);
+    }
+
+    #[test]
+    fn predict_next() {
+        assert_eq!(
+            predictor_from(&[10, 13, 16, 21, 30, 45]).predict_next(),
+            Ok(68)
+        );
+        assert_eq!(predictor_from(&[0, 3, 6, 9, 12, 15]).predict_next(), Ok(18));
+    }
+
+    #[test]
+    fn not_polynomial() {
+        assert_eq!(
+            predictor_from(&[1, 2, 4, 8, 16]).predict_next(),
+            Err(PredictorError::NotPolynomial)
+        );
+        assert_eq!(
+            predictor_from(&[7]).predict_next(),
+            Err(PredictorError::NotPolynomial)
+        );
+        assert_eq!(predictor_from(&[0]).predict_next(), Ok(0));
+    }
+
+    #[test]
+    fn predict_after_pattern_change() {
+        let mut predictor = predictor_from(&[0, 0, 0]);
+        assert_eq!(predictor.predict_next(), Ok(0));
+        predictor.push(1).unwrap();
+        assert_eq!(predictor.predict_next(), Err(PredictorError::NotPolynomial));
+        // 0 0 0 1 4 10 20 is a cubic, its third differences are all 1
+        for reading in [4, 10, 20] {
+            predictor.push(reading).unwrap();
+        }
+        assert_eq!(predictor.predict_next(), Ok(35));
+    }
+
+    #[test]
+    fn push_overflow() {
+        let mut predictor = predictor_from(&[i64::MIN]);
+        assert_eq!(predictor.push(i64::MAX), Err(PredictorError::Overflow));
+        // Left as it was
+        assert_eq!(predictor.edges, vec![i64::MIN]);
+    }
+}